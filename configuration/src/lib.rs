@@ -1,18 +1,90 @@
+mod reloader;
+mod schema;
+mod secret;
+mod secret_source;
+mod state;
 mod tests;
+mod validation;
+mod working_hours;
 
-use std::{fs::File, path::Path};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{Debug, Display, Formatter},
+    fs::{create_dir_all, read_to_string, set_permissions, OpenOptions, Permissions},
+    io::Write,
+    os::unix::fs::{OpenOptionsExt, PermissionsExt},
+    path::{Path, PathBuf},
+};
 
-use clap::Parser;
+use chrono::NaiveDate;
+use clap::{parser::ValueSource, ArgMatches, Args, Command, ValueEnum};
 use google_calendar3::yup_oauth2::ServiceAccountKey;
-use log::info;
+use log::{info, warn, LevelFilter};
+use regex::Regex;
+pub use reloader::{ApplicationConfigurationReloader, ConfigurationFileWatcher};
+pub use schema::get_application_configuration_json_schema;
+pub use secret::Secret;
+pub use secret_source::{
+    CommandSecretProvider, EnvSecretProvider, FileSecretProvider, KeyringSecretProvider, SecretProvider,
+    SecretResolver, SecretSource, COMMAND_SECRET_SCHEME, ENV_SECRET_SCHEME, FILE_SECRET_SCHEME, KEYRING_SECRET_SCHEME,
+};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+pub use state::{Pause, SyncState, SyncedStatus};
 use thiserror::Error;
+pub use validation::validate_calendar_id;
+use validation::{get_unknown_fields, validate_readable_file, validate_status_rules, validate_working_hours};
+pub use working_hours::{
+    parse_working_day_hours, IdleStatus, WorkingHours, WorkingLocationStatuses, WORKING_HOURS_FIELD_NAMES,
+    WORKING_LOCATION_STATUSES_FIELD_NAMES,
+};
 
-const DEFAULT_SERVICE_ACCOUNT_PATH: &str = ".secrets/.service_account.json";
-const DEFAULT_SLACK_USER_OAUTH_TOKEN_PATH: &str = ".secrets/.slack_user_oauth_token.json";
-const DEFAULT_LOGGING_CONFIG_PATH: &str = "config/logging_config.yaml";
-const DEFAULT_APPLICATION_CONFIG_PATH: &str = "config/application_config.json";
-const DEFAULT_CALENDAR_ID: &str = "primary";
+pub const DEFAULT_SERVICE_ACCOUNT_PATH: &str = ".secrets/.service_account.json";
+pub const DEFAULT_SLACK_USER_OAUTH_TOKEN_PATH: &str = ".secrets/.slack_user_oauth_token.json";
+pub const DEFAULT_LOGGING_CONFIG_PATH: &str = "config/logging_config.yaml";
+// Same as the default log directory of the logging crate.
+pub const DEFAULT_LOG_DIR: &str = "logs";
+pub const DEFAULT_AUDIT_LOG_FILE_NAME: &str = "audit.jsonl";
+pub const DEFAULT_STATE_PATH: &str = ".state/sync_state.json";
+pub const DEFAULT_APPLICATION_CONFIG_PATH: &str = "config/application_config.json";
+pub const DEFAULT_CALENDAR_ID: &str = "primary";
+pub const APPLICATION_NAME: &str = "slack-gcal-sync";
+pub const SYSTEM_CONFIG_DIR: &str = "/etc/slack-gcal-sync";
+pub const APPLICATION_CONFIG_FILE_STEM: &str = "application_config";
+pub const APPLICATION_CONFIG_FILE_EXTENSIONS: [&str; 4] = ["json", "yaml", "yml", "toml"];
+pub const CALENDAR_ID_ENV_VAR: &str = "SLACK_GCAL_SYNC_CALENDAR_ID";
+pub const SERVICE_ACCOUNT_KEY_PATH_ENV_VAR: &str = "SLACK_GCAL_SYNC_SERVICE_ACCOUNT_KEY_PATH";
+pub const SERVICE_ACCOUNT_KEY_ENV_VAR: &str = "SLACK_GCAL_SYNC_SERVICE_ACCOUNT_KEY";
+pub const SERVICE_ACCOUNT_KEY_SECRET_ENV_VAR: &str = "SLACK_GCAL_SYNC_SERVICE_ACCOUNT_KEY_SECRET";
+pub const SLACK_USER_OAUTH_TOKEN_PATH_ENV_VAR: &str = "SLACK_GCAL_SYNC_SLACK_USER_OAUTH_TOKEN_PATH";
+pub const SLACK_USER_OAUTH_TOKEN_ENV_VAR: &str = "SLACK_GCAL_SYNC_SLACK_USER_OAUTH_TOKEN";
+// Used before all environment variables got the `SLACK_GCAL_SYNC_` prefix.
+pub const DEPRECATED_SLACK_USER_OAUTH_TOKEN_ENV_VAR: &str = "SLACK_USER_OAUTH_TOKEN";
+pub const SLACK_USER_OAUTH_TOKEN_SECRET_ENV_VAR: &str = "SLACK_GCAL_SYNC_SLACK_USER_OAUTH_TOKEN_SECRET";
+pub const LOGGING_CONFIG_PATH_ENV_VAR: &str = "SLACK_GCAL_SYNC_LOGGING_CONFIG_PATH";
+pub const APPLICATION_CONFIG_PATH_ENV_VAR: &str = "SLACK_GCAL_SYNC_APPLICATION_CONFIG_PATH";
+pub const APPLICATION_CONFIG_FORMAT_ENV_VAR: &str = "SLACK_GCAL_SYNC_APPLICATION_CONFIG_FORMAT";
+pub const STRICT_CONFIG_ENV_VAR: &str = "SLACK_GCAL_SYNC_STRICT_CONFIG";
+pub const PROFILE_ENV_VAR: &str = "SLACK_GCAL_SYNC_PROFILE";
+pub const LOG_LEVEL_ENV_VAR: &str = "SLACK_GCAL_SYNC_LOG_LEVEL";
+pub const LOG_DIR_ENV_VAR: &str = "SLACK_GCAL_SYNC_LOG_DIR";
+pub const LOG_FILE_SIZE_ENV_VAR: &str = "SLACK_GCAL_SYNC_LOG_FILE_SIZE";
+pub const LOG_FILE_ARCHIVE_COUNT_ENV_VAR: &str = "SLACK_GCAL_SYNC_LOG_FILE_ARCHIVE_COUNT";
+pub const LOG_CONSOLE_FORMAT_ENV_VAR: &str = "SLACK_GCAL_SYNC_LOG_CONSOLE_FORMAT";
+pub const LOG_TO_FILE_ENV_VAR: &str = "SLACK_GCAL_SYNC_LOG_TO_FILE";
+pub const AUDIT_LOG_PATH_ENV_VAR: &str = "SLACK_GCAL_SYNC_AUDIT_LOG_PATH";
+pub const STATE_PATH_ENV_VAR: &str = "SLACK_GCAL_SYNC_STATE_PATH";
+// Options of the built-in logging configuration, not used when a logging configuration file is used.
+pub const BUILT_IN_LOGGING_FIELD_NAMES: [&str; 5] = [
+    "log_dir",
+    "log_file_size",
+    "log_file_archive_count",
+    "log_console_format",
+    "log_to_file",
+];
+pub const SECRET_FIELD_NAMES: [&str; 2] = ["service_account_key", "slack_user_oauth_token"];
+pub const MASKED_SECRET_VALUE: &str = "********";
+pub const SECRET_FILE_PERMISSIONS: u32 = 0o600;
+pub const CONFIGURATION_FILE_PERMISSIONS: u32 = 0o644;
 
 #[derive(Debug, Error)]
 pub enum ConfigurationError {
@@ -22,55 +94,503 @@ pub enum ConfigurationError {
     #[error("Cannot deserialize configuration from path: {0}. {1}")]
     DeserializeConfigurationError(String, String),
 
-    #[error("Missing value(s) for application configuration: {0}. Check application configuration file and command line arguments/environment variables.")]
+    #[error("Cannot parse configuration from: {0} at line {1}, column {2}. {3}")]
+    ParseConfigurationError(String, usize, usize, String),
+
+    #[error("Cannot write configuration to path: {0}. {1}")]
+    WriteConfigurationError(String, String),
+
+    #[error("Cannot read secret from '{0}' secret source. {1}")]
+    SecretError(String, String),
+
+    #[error("Missing value(s) for application configuration: {0}.")]
     InvalidConfigurationError(String),
+
+    #[error("Invalid value(s) in application configuration: {0}.")]
+    InvalidConfigurationValueError(String),
+}
+
+#[derive(ValueEnum, Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigurationFormat {
+    Json,
+    Yaml,
+    Toml,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl ConfigurationFormat {
+    // Files with unknown or missing extension are treated as json to keep existing configuration files working.
+    pub fn from_path(config_path: &str) -> ConfigurationFormat {
+        let extension = Path::new(config_path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_lowercase());
+        match extension.as_deref() {
+            Some("yaml") | Some("yml") => ConfigurationFormat::Yaml,
+            Some("toml") => ConfigurationFormat::Toml,
+            _ => ConfigurationFormat::Json,
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(log_level: LogLevel) -> LevelFilter {
+        match log_level {
+            LogLevel::Off => LevelFilter::Off,
+            LogLevel::Error => LevelFilter::Error,
+            LogLevel::Warn => LevelFilter::Warn,
+            LogLevel::Info => LevelFilter::Info,
+            LogLevel::Debug => LevelFilter::Debug,
+            LogLevel::Trace => LevelFilter::Trace,
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogConsoleFormat {
+    Pretty,
+    Json,
+    Compact,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigurationSource {
+    Default,
+    File(String),
+    CommandLine(String),
+    Environment(String),
+    Profile(String, Box<ConfigurationSource>),
+}
+
+impl Display for ConfigurationSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigurationSource::Default => write!(f, "default"),
+            ConfigurationSource::File(path) => write!(f, "file '{}'", path),
+            ConfigurationSource::CommandLine(argument) => write!(f, "command line argument {}", argument),
+            ConfigurationSource::Environment(env_var) => write!(f, "environment variable {}", env_var),
+            ConfigurationSource::Profile(profile, configuration_source) => {
+                write!(f, "profile '{}' in {}", profile, configuration_source)
+            }
+        }
+    }
+}
+
+// Maps configuration field names to the source their merged value comes from.
+pub type ConfigurationSources = HashMap<String, ConfigurationSource>;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ApplicationConfiguration {
     pub calendar_id: String,
-    pub service_account_key: ServiceAccountKey,
-    pub slack_user_oauth_token: String,
+    pub service_account_key: Secret<ServiceAccountKey>,
+    pub slack_user_oauth_token: Secret<String>,
     pub logging_config_path: String,
+    pub audit_log_path: String,
+    pub state_path: String,
+    pub rules: Vec<StatusRule>,
+    pub working_hours: Option<WorkingHours>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
-struct SlackUserOauthToken {
-    user_oauth_token: String,
+pub struct SlackUserOauthToken {
+    pub user_oauth_token: Secret<String>,
+}
+
+pub const STATUS_RULE_FIELD_NAMES: [&str; 9] = [
+    "summary_pattern",
+    "status_text",
+    "status_emoji",
+    "lead_minutes",
+    "lag_minutes",
+    "event_type",
+    "all_day_events",
+    "conference_emojis",
+    "join_link_field",
+];
+// Events are fetched for one day, so offsets longer than a day would never apply.
+pub const MAX_STATUS_RULE_OFFSET_MINUTES: u32 = 24 * 60;
+// Google Calendar event types a rule can be limited to, events without a type are `default` events. Working location
+// events are not meetings and never match rules.
+pub const DEFAULT_EVENT_TYPE: &str = "default";
+pub const STATUS_RULE_EVENT_TYPES: [&str; 5] =
+    [DEFAULT_EVENT_TYPE, "birthday", "focusTime", "fromGmail", "outOfOffice"];
+// Replaced in `status_text` with the date the user is back, e.g. "Back on {return_date}" becomes "Back on Mon 20 Jan".
+pub const RETURN_DATE_PLACEHOLDER: &str = "{return_date}";
+pub const STATUS_TEXT_PLACEHOLDERS: [&str; 1] = [RETURN_DATE_PLACEHOLDER];
+const RETURN_DATE_FORMAT: &str = "%a %-d %b";
+// Video conference providers recognised in calendar events.
+pub const CONFERENCE_PROVIDERS: [&str; 3] = ["meet", "zoom", "teams"];
+
+// Regular expression is compiled once when the configuration is loaded. An invalid pattern is kept with its error, so
+// validation reports it with the other invalid values.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(from = "String", into = "String")]
+pub struct SummaryPattern {
+    pattern: String,
+    regex: Result<Regex, regex::Error>,
+}
+
+impl SummaryPattern {
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn get_regex(&self) -> Result<&Regex, &regex::Error> {
+        self.regex.as_ref()
+    }
+}
+
+impl From<String> for SummaryPattern {
+    fn from(pattern: String) -> Self {
+        let regex = Regex::new(&pattern);
+        SummaryPattern { pattern, regex }
+    }
+}
+
+impl From<&str> for SummaryPattern {
+    fn from(pattern: &str) -> Self {
+        SummaryPattern::from(pattern.to_string())
+    }
 }
 
-#[derive(Parser, Debug, Serialize, Deserialize, Clone, PartialEq)]
-#[command(version, about)]
+impl From<SummaryPattern> for String {
+    fn from(summary_pattern: SummaryPattern) -> Self {
+        summary_pattern.pattern
+    }
+}
+
+impl PartialEq for SummaryPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern
+    }
+}
+
+impl Debug for SummaryPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.pattern)
+    }
+}
+
+impl Display for SummaryPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.pattern)
+    }
+}
+
+// Sets the Slack status of the user while a calendar event with summary matching the regular expression is ongoing.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct StatusRule {
+    pub summary_pattern: SummaryPattern,
+    pub status_text: String,
+    #[serde(default)]
+    pub status_emoji: String,
+    // Status is set this many minutes before the event starts, e.g. to show "Heading to a meeting".
+    #[serde(default)]
+    pub lead_minutes: u32,
+    // Status is kept this many minutes after the event ends, e.g. as a cool-down.
+    #[serde(default)]
+    pub lag_minutes: u32,
+    // Only events of this type match, e.g. `outOfOffice`. Events of any type match when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_type: Option<String>,
+    // All-day and multi-day all-day events, e.g. a week long "Vacation", match only rules which allow them.
+    #[serde(default)]
+    pub all_day_events: bool,
+    // Conference providers mapped to emoji codes used instead of `status_emoji` when the event has a video call, e.g.
+    // `zoom` to `:zoom:`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub conference_emojis: BTreeMap<String, String>,
+    // Id of a Slack custom profile field set to the join link of the video call, e.g. `Xf0123456789`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub join_link_field: Option<String>,
+}
+
+impl StatusRule {
+    // Rules are validated when configuration is loaded, an invalid pattern does not match anything.
+    pub fn matches(&self, summary: &str) -> bool {
+        self.summary_pattern
+            .get_regex()
+            .is_ok_and(|summary_regex| summary_regex.is_match(summary))
+    }
+
+    pub fn matches_event_type(&self, event_type: Option<&str>) -> bool {
+        self.event_type
+            .as_deref()
+            .is_none_or(|rule_event_type| rule_event_type == event_type.unwrap_or(DEFAULT_EVENT_TYPE))
+    }
+
+    pub fn get_status_emoji(&self, conference_provider: Option<&str>) -> String {
+        conference_provider
+            .and_then(|conference_provider| self.conference_emojis.get(conference_provider))
+            .unwrap_or(&self.status_emoji)
+            .clone()
+    }
+
+    pub fn get_status_text(&self, return_date: NaiveDate) -> String {
+        self.status_text.replace(
+            RETURN_DATE_PLACEHOLDER,
+            &return_date.format(RETURN_DATE_FORMAT).to_string(),
+        )
+    }
+}
+
+// Named set of values selected with `--profile`, overriding the values merged from configuration layers, e.g. to switch
+// between a test and a company Slack workspace.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct ProfileConfigurationData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub calendar_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slack_user_oauth_token_path: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slack_user_oauth_token: Option<Secret<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slack_user_oauth_token_secret: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rules: Option<Vec<StatusRule>>,
+}
+
+impl ProfileConfigurationData {
+    pub fn get_field_names() -> [&'static str; 5] {
+        [
+            "calendar_id",
+            "slack_user_oauth_token_path",
+            "slack_user_oauth_token",
+            "slack_user_oauth_token_secret",
+            "rules",
+        ]
+    }
+
+    pub fn get_set_field_names(&self) -> Vec<&'static str> {
+        let is_set = [
+            self.calendar_id.is_some(),
+            self.slack_user_oauth_token_path.is_some(),
+            self.slack_user_oauth_token.is_some(),
+            self.slack_user_oauth_token_secret.is_some(),
+            self.rules.is_some(),
+        ];
+        Self::get_field_names()
+            .into_iter()
+            .zip(is_set)
+            .filter_map(|(field, is_set)| is_set.then_some(field))
+            .collect()
+    }
+
+    fn sets_slack_user_oauth_token(&self) -> bool {
+        self.slack_user_oauth_token_path.is_some()
+            || self.slack_user_oauth_token.is_some()
+            || self.slack_user_oauth_token_secret.is_some()
+    }
+}
+
+#[derive(Args, Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct ApplicationConfigurationData {
+    #[serde(skip_serializing_if = "Option::is_none")]
     #[arg(
         short,
         long,
+        env = CALENDAR_ID_ENV_VAR,
         help = "The id of the calendar to synchronize. Usually your gmail email account."
     )]
     pub calendar_id: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
     #[arg(
         short,
         long,
+        env = SERVICE_ACCOUNT_KEY_PATH_ENV_VAR,
         help = "Path to the service account key json file from Google Cloud Project."
     )]
     pub service_account_key_path: Option<String>,
 
-    #[arg(short = 't', long, help = "Path to the slack user oauth token json file.")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(
+        long,
+        env = SERVICE_ACCOUNT_KEY_ENV_VAR,
+        hide_env_values = true,
+        help = "Contents of the service account key json file from Google Cloud Project. Takes precedence over service account key path."
+    )]
+    pub service_account_key: Option<Secret<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(
+        long,
+        env = SERVICE_ACCOUNT_KEY_SECRET_ENV_VAR,
+        help = "Secret source of the service account key json, e.g. `cmd:pass show gcal-service-account`, `env:GCAL_KEY`, `keyring:slack-gcal-sync/service-account` or `file:path`. Takes precedence over service account key path."
+    )]
+    pub service_account_key_secret: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(
+        short = 't',
+        long,
+        env = SLACK_USER_OAUTH_TOKEN_PATH_ENV_VAR,
+        help = "Path to the slack user oauth token json file."
+    )]
     pub slack_user_oauth_token_path: Option<String>,
 
-    #[arg(env, help = "Environment variable holding value of slack user oauth token.")]
-    pub slack_user_oauth_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(
+        env = SLACK_USER_OAUTH_TOKEN_ENV_VAR,
+        hide_env_values = true,
+        help = "Value of slack user oauth token. Takes precedence over slack user oauth token path."
+    )]
+    pub slack_user_oauth_token: Option<Secret<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(
+        long,
+        env = SLACK_USER_OAUTH_TOKEN_SECRET_ENV_VAR,
+        help = "Secret source of the slack user oauth token, e.g. `cmd:pass show slack-token`, `env:SLACK_TOKEN`, `keyring:slack-gcal-sync/slack-token` or `file:path`. Takes precedence over slack user oauth token path."
+    )]
+    pub slack_user_oauth_token_secret: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
     #[arg(
         short,
         long,
+        env = LOGGING_CONFIG_PATH_ENV_VAR,
         help = "Path to the log4rs logging configuration file. See https://docs.rs/log4rs/latest/log4rs/#configuration-via-a-yaml-file for possible options."
     )]
     pub logging_config_path: Option<String>,
 
-    #[arg(short, long, default_value = DEFAULT_APPLICATION_CONFIG_PATH, help = "Path to the application configuration file.")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(
+        long,
+        value_enum,
+        env = LOG_LEVEL_ENV_VAR,
+        help = "Log level of all modules. Overrides the root level of the logging configuration file as well. [default: info]"
+    )]
+    pub log_level: Option<LogLevel>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(
+        long,
+        env = LOG_DIR_ENV_VAR,
+        help = "Directory of log files when no logging configuration file is used. [default: logs]"
+    )]
+    pub log_dir: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(
+        long,
+        env = LOG_FILE_SIZE_ENV_VAR,
+        help = "Size of the log file in bytes at which it is archived, when no logging configuration file is used. [default: 10485760]"
+    )]
+    pub log_file_size: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(
+        long,
+        env = LOG_FILE_ARCHIVE_COUNT_ENV_VAR,
+        help = "Number of archived log files kept, when no logging configuration file is used. [default: 5]"
+    )]
+    pub log_file_archive_count: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(
+        long,
+        value_enum,
+        env = LOG_CONSOLE_FORMAT_ENV_VAR,
+        help = "Format of console logs when no logging configuration file is used. [default: pretty]"
+    )]
+    pub log_console_format: Option<LogConsoleFormat>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(
+        long,
+        env = LOG_TO_FILE_ENV_VAR,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        help = "Write logs to files as well as to the console, when no logging configuration file is used. Use --log-to-file=false to log to the console only. [default: true]"
+    )]
+    pub log_to_file: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(
+        long,
+        env = AUDIT_LOG_PATH_ENV_VAR,
+        help = "Path to the JSON Lines audit log of Slack status changes. [default: audit.jsonl in the log directory]"
+    )]
+    pub audit_log_path: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(
+        long,
+        env = STATE_PATH_ENV_VAR,
+        help = "Path to the file keeping synchronization state, e.g. pauses and the last status set. [default: .state/sync_state.json]"
+    )]
+    pub state_path: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(
+        short,
+        long,
+        env = APPLICATION_CONFIG_PATH_ENV_VAR,
+        default_value = DEFAULT_APPLICATION_CONFIG_PATH,
+        help = "Path to the application configuration file."
+    )]
     pub application_config_path: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(
+        long,
+        value_enum,
+        env = APPLICATION_CONFIG_FORMAT_ENV_VAR,
+        help = "Format of the application configuration file. Detected from the file extension if not given: .yaml/.yml, .toml, anything else is read as json."
+    )]
+    pub application_config_format: Option<ConfigurationFormat>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(
+        long,
+        env = STRICT_CONFIG_ENV_VAR,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        help = "Reject unknown fields in application configuration files instead of ignoring them with a warning."
+    )]
+    pub strict_config: Option<bool>,
+
+    // Rules can only be set in configuration files.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(skip)]
+    pub rules: Option<Vec<StatusRule>>,
+
+    // Working hours can only be set in configuration files.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(skip)]
+    pub working_hours: Option<WorkingHours>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(
+        long,
+        env = PROFILE_ENV_VAR,
+        help = "Name of the profile from application configuration files to use, e.g. `work`. Values of the profile override values from configuration files."
+    )]
+    pub profile: Option<String>,
+
+    // Profiles can only be set in configuration files.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(skip)]
+    pub profiles: Option<BTreeMap<String, ProfileConfigurationData>>,
 }
 
 impl Default for ApplicationConfigurationData {
@@ -78,15 +598,191 @@ impl Default for ApplicationConfigurationData {
         ApplicationConfigurationData {
             calendar_id: Some(DEFAULT_CALENDAR_ID.to_string()),
             service_account_key_path: Some(DEFAULT_SERVICE_ACCOUNT_PATH.to_string()),
+            service_account_key: None,
+            service_account_key_secret: None,
             slack_user_oauth_token_path: Some(DEFAULT_SLACK_USER_OAUTH_TOKEN_PATH.to_string()),
             slack_user_oauth_token: None,
+            slack_user_oauth_token_secret: None,
             logging_config_path: Some(DEFAULT_LOGGING_CONFIG_PATH.to_string()),
+            log_level: None,
+            log_dir: None,
+            log_file_size: None,
+            log_file_archive_count: None,
+            log_console_format: None,
+            log_to_file: None,
+            audit_log_path: None,
+            state_path: None,
             application_config_path: Some(DEFAULT_APPLICATION_CONFIG_PATH.to_string()),
+            application_config_format: None,
+            strict_config: None,
+            rules: None,
+            working_hours: None,
+            profile: None,
+            profiles: None,
         }
     }
 }
 
 impl ApplicationConfigurationData {
+    pub fn empty() -> Self {
+        ApplicationConfigurationData {
+            calendar_id: None,
+            service_account_key_path: None,
+            service_account_key: None,
+            service_account_key_secret: None,
+            slack_user_oauth_token_path: None,
+            slack_user_oauth_token: None,
+            slack_user_oauth_token_secret: None,
+            logging_config_path: None,
+            log_level: None,
+            log_dir: None,
+            log_file_size: None,
+            log_file_archive_count: None,
+            log_console_format: None,
+            log_to_file: None,
+            audit_log_path: None,
+            state_path: None,
+            application_config_path: None,
+            application_config_format: None,
+            strict_config: None,
+            rules: None,
+            working_hours: None,
+            profile: None,
+            profiles: None,
+        }
+    }
+
+    // Audit log is kept next to the log files unless its path is given.
+    pub fn get_audit_log_path(&self) -> String {
+        match &self.audit_log_path {
+            Some(audit_log_path) => audit_log_path.clone(),
+            None => Path::new(self.log_dir.as_deref().unwrap_or(DEFAULT_LOG_DIR))
+                .join(DEFAULT_AUDIT_LOG_FILE_NAME)
+                .display()
+                .to_string(),
+        }
+    }
+
+    pub fn get_state_path(&self) -> String {
+        self.state_path.clone().unwrap_or(DEFAULT_STATE_PATH.to_string())
+    }
+
+    pub fn get_field_values(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("calendar_id", self.calendar_id.clone()),
+            ("service_account_key_path", self.service_account_key_path.clone()),
+            (
+                "service_account_key",
+                self.service_account_key.as_ref().map(|key| key.expose_secret().clone()),
+            ),
+            ("service_account_key_secret", self.service_account_key_secret.clone()),
+            ("slack_user_oauth_token_path", self.slack_user_oauth_token_path.clone()),
+            (
+                "slack_user_oauth_token",
+                self.slack_user_oauth_token
+                    .as_ref()
+                    .map(|token| token.expose_secret().clone()),
+            ),
+            (
+                "slack_user_oauth_token_secret",
+                self.slack_user_oauth_token_secret.clone(),
+            ),
+            ("logging_config_path", self.logging_config_path.clone()),
+            (
+                "log_level",
+                self.log_level
+                    .and_then(|log_level| log_level.to_possible_value())
+                    .map(|log_level| log_level.get_name().to_string()),
+            ),
+            ("log_dir", self.log_dir.clone()),
+            (
+                "log_file_size",
+                self.log_file_size.map(|log_file_size| log_file_size.to_string()),
+            ),
+            (
+                "log_file_archive_count",
+                self.log_file_archive_count
+                    .map(|log_file_archive_count| log_file_archive_count.to_string()),
+            ),
+            (
+                "log_console_format",
+                self.log_console_format
+                    .and_then(|log_console_format| log_console_format.to_possible_value())
+                    .map(|log_console_format| log_console_format.get_name().to_string()),
+            ),
+            (
+                "log_to_file",
+                self.log_to_file.map(|log_to_file| log_to_file.to_string()),
+            ),
+            ("audit_log_path", self.audit_log_path.clone()),
+            ("state_path", self.state_path.clone()),
+            ("application_config_path", self.application_config_path.clone()),
+            (
+                "application_config_format",
+                self.application_config_format
+                    .and_then(|format| format.to_possible_value())
+                    .map(|format| format.get_name().to_string()),
+            ),
+            (
+                "strict_config",
+                self.strict_config.map(|strict_config| strict_config.to_string()),
+            ),
+            (
+                "rules",
+                self.rules.as_ref().map(|rules| serde_json::to_string(rules).unwrap()),
+            ),
+            (
+                "working_hours",
+                self.working_hours
+                    .as_ref()
+                    .map(|working_hours| serde_json::to_string(working_hours).unwrap()),
+            ),
+            ("profile", self.profile.clone()),
+            (
+                "profiles",
+                self.profiles
+                    .as_ref()
+                    .map(|profiles| profiles.keys().cloned().collect::<Vec<String>>().join(", ")),
+            ),
+        ]
+    }
+
+    pub fn get_masked_field_values(&self) -> Vec<(&'static str, Option<String>)> {
+        self.get_field_values()
+            .into_iter()
+            .map(|(field, value)| match SECRET_FIELD_NAMES.contains(&field) {
+                true => (field, value.map(|_| MASKED_SECRET_VALUE.to_string())),
+                false => (field, value),
+            })
+            .collect()
+    }
+
+    // Argument matches tell if a value came from a command line argument, an environment variable or a default value.
+    pub fn get_cli_configuration_sources(arg_matches: &ArgMatches) -> ConfigurationSources {
+        let command = Self::augment_args(Command::new("application_configuration_data"));
+        command
+            .get_arguments()
+            .filter_map(|arg| {
+                let field = arg.get_id().as_str();
+                let source = match arg_matches
+                    .try_get_raw(field)
+                    .ok()?
+                    .and(arg_matches.value_source(field))?
+                {
+                    ValueSource::CommandLine => ConfigurationSource::CommandLine(match arg.get_long() {
+                        Some(long) => format!("--{}", long),
+                        None => field.to_uppercase(),
+                    }),
+                    ValueSource::EnvVariable => {
+                        ConfigurationSource::Environment(arg.get_env()?.to_string_lossy().to_string())
+                    }
+                    _ => ConfigurationSource::Default,
+                };
+                Some((field.to_string(), source))
+            })
+            .collect()
+    }
+
     fn update(&mut self, other: ApplicationConfigurationData) {
         if let Some(calendar_id) = other.calendar_id.as_ref() {
             self.calendar_id = Some(calendar_id.to_string());
@@ -94,75 +790,302 @@ impl ApplicationConfigurationData {
         if let Some(service_account_key_path) = other.service_account_key_path.as_ref() {
             self.service_account_key_path = Some(service_account_key_path.to_string());
         }
+        if let Some(service_account_key) = other.service_account_key {
+            self.service_account_key = Some(service_account_key);
+        }
+        if let Some(service_account_key_secret) = other.service_account_key_secret.as_ref() {
+            self.service_account_key_secret = Some(service_account_key_secret.to_string());
+        }
         if let Some(slack_user_oauth_token_path) = other.slack_user_oauth_token_path.as_ref() {
             self.slack_user_oauth_token_path = Some(slack_user_oauth_token_path.to_string());
         }
-        if let Some(slack_user_oauth_token) = other.slack_user_oauth_token.as_ref() {
-            self.slack_user_oauth_token = Some(slack_user_oauth_token.to_string());
+        if let Some(slack_user_oauth_token) = other.slack_user_oauth_token {
+            self.slack_user_oauth_token = Some(slack_user_oauth_token);
+        }
+        if let Some(slack_user_oauth_token_secret) = other.slack_user_oauth_token_secret.as_ref() {
+            self.slack_user_oauth_token_secret = Some(slack_user_oauth_token_secret.to_string());
         }
         if let Some(logging_config_path) = other.logging_config_path.as_ref() {
             self.logging_config_path = Some(logging_config_path.to_string());
         }
+        if let Some(log_level) = other.log_level {
+            self.log_level = Some(log_level);
+        }
+        if let Some(log_dir) = other.log_dir {
+            self.log_dir = Some(log_dir);
+        }
+        if let Some(log_file_size) = other.log_file_size {
+            self.log_file_size = Some(log_file_size);
+        }
+        if let Some(log_file_archive_count) = other.log_file_archive_count {
+            self.log_file_archive_count = Some(log_file_archive_count);
+        }
+        if let Some(log_console_format) = other.log_console_format {
+            self.log_console_format = Some(log_console_format);
+        }
+        if let Some(log_to_file) = other.log_to_file {
+            self.log_to_file = Some(log_to_file);
+        }
+        if let Some(audit_log_path) = other.audit_log_path.as_ref() {
+            self.audit_log_path = Some(audit_log_path.to_string());
+        }
+        if let Some(state_path) = other.state_path.as_ref() {
+            self.state_path = Some(state_path.to_string());
+        }
         if let Some(application_config_path) = other.application_config_path.as_ref() {
             self.application_config_path = Some(application_config_path.to_string());
         }
+        if let Some(application_config_format) = other.application_config_format {
+            self.application_config_format = Some(application_config_format);
+        }
+        if let Some(strict_config) = other.strict_config {
+            self.strict_config = Some(strict_config);
+        }
+        // Rules of a layer replace rules of the earlier layers as a whole.
+        if let Some(rules) = other.rules {
+            self.rules = Some(rules);
+        }
+        // Working hours of a layer replace working hours of the earlier layers as a whole.
+        if let Some(working_hours) = other.working_hours {
+            self.working_hours = Some(working_hours);
+        }
+        if let Some(profile) = other.profile {
+            self.profile = Some(profile);
+        }
+        // Profiles are merged by name, a profile replaces the profile with the same name from the earlier layers.
+        if let Some(profiles) = other.profiles {
+            self.profiles.get_or_insert_with(BTreeMap::new).extend(profiles);
+        }
     }
+
+    fn apply_profile(&mut self, profile: &ProfileConfigurationData) {
+        if let Some(calendar_id) = profile.calendar_id.as_ref() {
+            self.calendar_id = Some(calendar_id.to_string());
+        }
+        // Token sources of the profile replace all token sources, so a token from configuration files, which would
+        // take precedence over e.g. a token path of the profile, is not used by mistake.
+        if profile.sets_slack_user_oauth_token() {
+            self.slack_user_oauth_token_path = profile.slack_user_oauth_token_path.clone();
+            self.slack_user_oauth_token = profile.slack_user_oauth_token.clone();
+            self.slack_user_oauth_token_secret = profile.slack_user_oauth_token_secret.clone();
+        }
+        if let Some(rules) = profile.rules.as_ref() {
+            self.rules = Some(rules.clone());
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigurationLayer {
+    pub name: String,
+    pub source: ConfigurationSource,
+    pub application_configuration_data: ApplicationConfigurationData,
+    pub unknown_fields: Vec<String>,
 }
 
+// Layers are ordered from the lowest precedence: built-in defaults, system, user and project configuration files.
+// Environment variables and command line arguments are applied on top of them.
 pub struct ApplicationConfigurationGetter {
     cli_application_configuration_data: ApplicationConfigurationData,
-    file_application_configuration_data: ApplicationConfigurationData,
+    cli_configuration_sources: ConfigurationSources,
+    configuration_layers: Vec<ConfigurationLayer>,
+    configuration_file_locations: Vec<(String, String)>,
+    configuration_file_paths: Vec<String>,
+    secret_resolver: SecretResolver,
 }
 
 impl ApplicationConfigurationGetter {
     pub fn new(cli_application_configuration_data: ApplicationConfigurationData) -> Result<Self, ConfigurationError> {
-        let file_application_configuration_data = Self::load_from_file_or_default(
-            cli_application_configuration_data
-                .application_config_path
-                .clone()
-                .unwrap_or(DEFAULT_APPLICATION_CONFIG_PATH.to_string())
-                .as_str(),
-        )?;
-        Ok(ApplicationConfigurationGetter {
+        Self::new_with_config_dirs(
             cli_application_configuration_data,
-            file_application_configuration_data,
-        })
+            Some(PathBuf::from(SYSTEM_CONFIG_DIR)),
+            get_user_config_dir(),
+        )
     }
 
-    fn load_from_file_or_default(
-        application_config_path: &str,
-    ) -> Result<ApplicationConfigurationData, ConfigurationError> {
-        let file_application_configuration_data: ApplicationConfigurationData =
-            if Path::new(application_config_path).exists() {
-                info!(
-                    "Loading application configuration from file at path: '{}'.",
-                    application_config_path
-                );
-                let file_application_configuration_data =
-                    read_json_configuration::<ApplicationConfigurationData>(&application_config_path.to_string())?;
-                info!(
-                    "Successfully loaded application configuration from file at path: '{}'.",
-                    application_config_path
-                );
-                file_application_configuration_data
-            } else {
-                info!(
-                    "No application configuration file found at path: '{}'. Using default settings. \
-         Make sure to provide configuration via command line args.",
-                    application_config_path
-                );
-                ApplicationConfigurationData::default()
+    pub fn new_with_config_dirs(
+        cli_application_configuration_data: ApplicationConfigurationData,
+        system_config_dir: Option<PathBuf>,
+        user_config_dir: Option<PathBuf>,
+    ) -> Result<Self, ConfigurationError> {
+        let mut configuration_layers = vec![ConfigurationLayer {
+            name: "built-in defaults".to_string(),
+            source: ConfigurationSource::Default,
+            application_configuration_data: ApplicationConfigurationData::default(),
+            unknown_fields: Vec::new(),
+        }];
+        let mut configuration_file_locations = Vec::new();
+        let mut configuration_file_paths = Vec::new();
+
+        for (name, config_dir) in [
+            ("system config file", system_config_dir),
+            ("user config file", user_config_dir),
+        ] {
+            let Some(config_dir) = config_dir else {
+                continue;
             };
-        Ok(file_application_configuration_data)
+            configuration_file_locations.push((
+                name.to_string(),
+                format!(
+                    "{}/{}.{{{}}}",
+                    config_dir.display(),
+                    APPLICATION_CONFIG_FILE_STEM,
+                    APPLICATION_CONFIG_FILE_EXTENSIONS.join(",")
+                ),
+            ));
+            configuration_file_paths.extend(APPLICATION_CONFIG_FILE_EXTENSIONS.iter().map(|extension| {
+                config_dir
+                    .join(format!("{}.{}", APPLICATION_CONFIG_FILE_STEM, extension))
+                    .display()
+                    .to_string()
+            }));
+            if let Some(config_path) = find_application_config_file(&config_dir) {
+                configuration_layers.push(Self::load_configuration_layer(name, &config_path, None)?);
+            }
+        }
+
+        let application_config_path = cli_application_configuration_data
+            .application_config_path
+            .clone()
+            .unwrap_or(DEFAULT_APPLICATION_CONFIG_PATH.to_string());
+        configuration_file_locations.push(("project config file".to_string(), application_config_path.clone()));
+        configuration_file_paths.push(application_config_path.clone());
+        if Path::new(&application_config_path).exists() {
+            configuration_layers.push(Self::load_configuration_layer(
+                "project config file",
+                &application_config_path,
+                cli_application_configuration_data.application_config_format,
+            )?);
+        } else {
+            info!(
+                "No application configuration file found at path: '{}'. Using other configuration layers.",
+                application_config_path
+            );
+        }
+
+        let mut application_configuration_getter = ApplicationConfigurationGetter {
+            cli_application_configuration_data,
+            cli_configuration_sources: ConfigurationSources::new(),
+            configuration_layers,
+            configuration_file_locations,
+            configuration_file_paths,
+            secret_resolver: SecretResolver::default(),
+        };
+        application_configuration_getter
+            .use_deprecated_slack_user_oauth_token(std::env::var(DEPRECATED_SLACK_USER_OAUTH_TOKEN_ENV_VAR).ok());
+        Ok(application_configuration_getter)
+    }
+
+    // Token from the deprecated environment variable is used like a value of an environment variable, only when the
+    // token is not given with the current one or a command line argument.
+    fn use_deprecated_slack_user_oauth_token(&mut self, slack_user_oauth_token: Option<String>) {
+        let Some(slack_user_oauth_token) = slack_user_oauth_token else {
+            return;
+        };
+        if self.cli_application_configuration_data.slack_user_oauth_token.is_some() {
+            return;
+        }
+        warn!(
+            "Environment variable {} is deprecated and will be removed, use {} instead.",
+            DEPRECATED_SLACK_USER_OAUTH_TOKEN_ENV_VAR, SLACK_USER_OAUTH_TOKEN_ENV_VAR
+        );
+        self.cli_application_configuration_data.slack_user_oauth_token = Some(Secret::new(slack_user_oauth_token));
+        self.cli_configuration_sources.insert(
+            "slack_user_oauth_token".to_string(),
+            ConfigurationSource::Environment(DEPRECATED_SLACK_USER_OAUTH_TOKEN_ENV_VAR.to_string()),
+        );
+    }
+
+    // Without sources all values given to the getter are reported as coming from command line arguments.
+    pub fn with_cli_configuration_sources(mut self, cli_configuration_sources: ConfigurationSources) -> Self {
+        self.cli_configuration_sources.extend(cli_configuration_sources);
+        self
+    }
+
+    pub fn with_secret_resolver(mut self, secret_resolver: SecretResolver) -> Self {
+        self.secret_resolver = secret_resolver;
+        self
+    }
+
+    fn load_configuration_layer(
+        name: &str,
+        application_config_path: &str,
+        application_config_format: Option<ConfigurationFormat>,
+    ) -> Result<ConfigurationLayer, ConfigurationError> {
+        info!(
+            "Loading application configuration from {} at path: '{}'.",
+            name, application_config_path
+        );
+        let application_config_format =
+            application_config_format.unwrap_or(ConfigurationFormat::from_path(application_config_path));
+        let configuration = read_to_string(application_config_path)?;
+        let application_configuration_data = parse_configuration::<ApplicationConfigurationData>(
+            &configuration,
+            application_config_path,
+            application_config_format,
+        )?;
+        // Serde ignores unknown fields, so they are found by comparing the raw configuration with known field names.
+        let unknown_fields = get_unknown_fields(&parse_configuration::<serde_json::Value>(
+            &configuration,
+            application_config_path,
+            application_config_format,
+        )?);
+        if !unknown_fields.is_empty() {
+            warn!(
+                "Found unknown field(s) in {} at path: '{}': {}.",
+                name,
+                application_config_path,
+                unknown_fields.join(", ")
+            );
+        }
+        info!(
+            "Successfully loaded application configuration from {} at path: '{}'.",
+            name, application_config_path
+        );
+        Ok(ConfigurationLayer {
+            name: name.to_string(),
+            source: ConfigurationSource::File(application_config_path.to_string()),
+            application_configuration_data,
+            unknown_fields,
+        })
+    }
+
+    pub fn get_configuration_layers(&self) -> &[ConfigurationLayer] {
+        &self.configuration_layers
+    }
+
+    // Profiles defined in configuration layers together with the layer source, later layers replace profiles with the
+    // same name.
+    pub fn get_profiles(&self) -> BTreeMap<String, (ProfileConfigurationData, ConfigurationSource)> {
+        let mut profiles = BTreeMap::new();
+        for configuration_layer in &self.configuration_layers {
+            for (name, profile) in configuration_layer
+                .application_configuration_data
+                .profiles
+                .iter()
+                .flatten()
+            {
+                profiles.insert(name.clone(), (profile.clone(), configuration_layer.source.clone()));
+            }
+        }
+        profiles
     }
 
-    fn get_service_account_key(&self, service_account_key_path: &str) -> Result<ServiceAccountKey, ConfigurationError> {
+    // Every file which can hold a configuration layer, including the ones which do not exist yet.
+    pub fn get_configuration_file_paths(&self) -> &[String] {
+        &self.configuration_file_paths
+    }
+
+    pub fn get_service_account_key(
+        &self,
+        service_account_key_path: &str,
+    ) -> Result<ServiceAccountKey, ConfigurationError> {
         info!(
             "Reading google calendar service account key from path: '{}'.",
             service_account_key_path
         );
 
-        match read_json_configuration::<ServiceAccountKey>(&service_account_key_path.to_string()) {
+        match read_json_configuration::<ServiceAccountKey>(service_account_key_path) {
             Ok(service_account_key) => {
                 info!("Successfully read google calendar service account key.");
                 Ok(service_account_key)
@@ -174,7 +1097,40 @@ impl ApplicationConfigurationGetter {
         }
     }
 
-    fn get_slack_user_oauth_token(
+    pub fn get_service_account_key_from_json(
+        &self,
+        service_account_key_json: &str,
+    ) -> Result<ServiceAccountKey, ConfigurationError> {
+        info!("Reading google calendar service account key from configuration value.");
+        let service_account_key = parse_configuration::<ServiceAccountKey>(
+            service_account_key_json,
+            SERVICE_ACCOUNT_KEY_ENV_VAR,
+            ConfigurationFormat::Json,
+        )?;
+        info!("Successfully read google calendar service account key.");
+        Ok(service_account_key)
+    }
+
+    pub fn get_service_account_key_from_secret(
+        &self,
+        service_account_key_secret: &str,
+    ) -> Result<ServiceAccountKey, ConfigurationError> {
+        let service_account_key_json = self.secret_resolver.get_secret(service_account_key_secret)?;
+        parse_configuration::<ServiceAccountKey>(
+            service_account_key_json.expose_secret(),
+            "service account key secret",
+            ConfigurationFormat::Json,
+        )
+    }
+
+    pub fn get_slack_user_oauth_token_from_secret(
+        &self,
+        slack_user_oauth_token_secret: &str,
+    ) -> Result<Secret<String>, ConfigurationError> {
+        self.secret_resolver.get_secret(slack_user_oauth_token_secret)
+    }
+
+    pub fn get_slack_user_oauth_token(
         &self,
         slack_user_oauth_token_path: &str,
     ) -> Result<SlackUserOauthToken, ConfigurationError> {
@@ -183,7 +1139,7 @@ impl ApplicationConfigurationGetter {
             slack_user_oauth_token_path
         );
 
-        match read_json_configuration::<SlackUserOauthToken>(&slack_user_oauth_token_path.to_string()) {
+        match read_json_configuration::<SlackUserOauthToken>(slack_user_oauth_token_path) {
             Ok(slack_user_oauth_token) => {
                 info!("Successfully read slack user oauth token.");
                 Ok(slack_user_oauth_token)
@@ -195,80 +1151,455 @@ impl ApplicationConfigurationGetter {
         }
     }
 
+    // Points to every place where a value of the field can be set, from configuration files to command line arguments.
+    fn get_missing_value_hint(&self, fields: &[&str]) -> String {
+        let command = ApplicationConfigurationData::augment_args(Command::new("application_configuration_data"));
+        let mut locations: Vec<String> = self
+            .configuration_file_locations
+            .iter()
+            .map(|(name, location)| format!("{} '{}'", name, location))
+            .collect();
+        for field in fields {
+            if let Some(arg) = command.get_arguments().find(|arg| arg.get_id() == *field) {
+                if let Some(env_var) = arg.get_env() {
+                    locations.push(format!("environment variable {}", env_var.to_string_lossy()));
+                }
+                locations.push(match arg.get_long() {
+                    Some(long) => format!("command line argument --{}", long),
+                    None => format!("command line argument {}", field.to_uppercase()),
+                });
+            }
+        }
+        format!("set it in one of: {}", locations.join(", "))
+    }
+
     fn validate(
         &self,
         application_configuration_data: &ApplicationConfigurationData,
+        configuration_sources: &ConfigurationSources,
     ) -> Result<(), ConfigurationError> {
         let mut missing_fields = Vec::new();
 
         if application_configuration_data.calendar_id.is_none() {
-            missing_fields.push("calendar_id is required".to_string());
+            missing_fields.push(format!(
+                "calendar_id is required ({})",
+                self.get_missing_value_hint(&["calendar_id"])
+            ));
         }
-        if application_configuration_data.service_account_key_path.is_none() {
-            missing_fields.push("service_account_key_path is required".to_string());
+        if application_configuration_data.service_account_key_path.is_none()
+            && application_configuration_data.service_account_key.is_none()
+            && application_configuration_data.service_account_key_secret.is_none()
+        {
+            missing_fields.push(format!(
+                "Either service_account_key_path, service_account_key_secret or service_account_key must be set ({})",
+                self.get_missing_value_hint(&[
+                    "service_account_key_path",
+                    "service_account_key_secret",
+                    "service_account_key"
+                ])
+            ));
         }
         if application_configuration_data.slack_user_oauth_token_path.is_none()
             && application_configuration_data.slack_user_oauth_token.is_none()
+            && application_configuration_data.slack_user_oauth_token_secret.is_none()
         {
-            missing_fields.push("Either slack_user_oauth_token_path or slack_user_oauth_token must be set".to_string());
+            missing_fields.push(format!(
+                "Either slack_user_oauth_token_path, slack_user_oauth_token_secret or slack_user_oauth_token must be set ({})",
+                self.get_missing_value_hint(&[
+                    "slack_user_oauth_token_path",
+                    "slack_user_oauth_token_secret",
+                    "slack_user_oauth_token"
+                ])
+            ));
         }
         if application_configuration_data.logging_config_path.is_none() {
-            missing_fields.push("logging_config_path is required".to_string());
+            missing_fields.push(format!(
+                "logging_config_path is required ({})",
+                self.get_missing_value_hint(&["logging_config_path"])
+            ));
         }
         // We set the default value in cli args to always contain application_config_path. Therefore, currently
         // validation for application_config_path should always pass. It is kept in validation though to provide a guard
         // for the future if logic changes or default is removed.
         if application_configuration_data.application_config_path.is_none() {
-            missing_fields.push("application_config_path is required".to_string());
+            missing_fields.push(format!(
+                "application_config_path is required ({})",
+                self.get_missing_value_hint(&["application_config_path"])
+            ));
         }
 
-        if missing_fields.is_empty() {
+        if !missing_fields.is_empty() {
+            return Err(ConfigurationError::InvalidConfigurationError(missing_fields.join("; ")));
+        }
+
+        let invalid_values = self.get_invalid_values(application_configuration_data, configuration_sources);
+        if invalid_values.is_empty() {
             Ok(())
         } else {
-            Err(ConfigurationError::InvalidConfigurationError(missing_fields.join(", ")))
+            Err(ConfigurationError::InvalidConfigurationValueError(
+                invalid_values.join("; "),
+            ))
         }
     }
 
-    pub fn get_application_configuration(&self) -> Result<ApplicationConfiguration, ConfigurationError> {
-        let mut merged_application_configuration_data = self.file_application_configuration_data.clone();
+    fn get_invalid_values(
+        &self,
+        application_configuration_data: &ApplicationConfigurationData,
+        configuration_sources: &ConfigurationSources,
+    ) -> Vec<String> {
+        let get_source = |field: &str| match configuration_sources.get(field) {
+            Some(configuration_source) => format!(" (from {})", configuration_source),
+            None => String::new(),
+        };
+        let mut invalid_values = Vec::new();
+
+        if let Some(calendar_id) = application_configuration_data.calendar_id.as_deref() {
+            if let Err(err) = validate_calendar_id(calendar_id) {
+                invalid_values.push(format!("calendar_id {}{}", err, get_source("calendar_id")));
+            }
+        }
+        // Files are checked only when they are going to be read.
+        let mut read_file_fields = Vec::new();
+        if application_configuration_data.service_account_key.is_none()
+            && application_configuration_data.service_account_key_secret.is_none()
+        {
+            read_file_fields.push((
+                "service_account_key_path",
+                application_configuration_data.service_account_key_path.as_deref(),
+            ));
+        }
+        if application_configuration_data.slack_user_oauth_token.is_none()
+            && application_configuration_data.slack_user_oauth_token_secret.is_none()
+        {
+            read_file_fields.push((
+                "slack_user_oauth_token_path",
+                application_configuration_data.slack_user_oauth_token_path.as_deref(),
+            ));
+        }
+        for (field, path) in read_file_fields {
+            if let Some(Err(err)) = path.map(validate_readable_file) {
+                invalid_values.push(format!("{} {}{}", field, err, get_source(field)));
+            }
+        }
+        // Default logging configuration is used when the logging configuration file does not exist.
+        if let Some(logging_config_path) = application_configuration_data.logging_config_path.as_deref() {
+            if Path::new(logging_config_path).exists() {
+                if let Err(err) = validate_readable_file(logging_config_path) {
+                    invalid_values.push(format!(
+                        "logging_config_path {}{}",
+                        err,
+                        get_source("logging_config_path")
+                    ));
+                }
+            }
+        }
+        for (field, value) in [
+            ("log_file_size", application_configuration_data.log_file_size),
+            (
+                "log_file_archive_count",
+                application_configuration_data.log_file_archive_count.map(u64::from),
+            ),
+        ] {
+            if value == Some(0) {
+                invalid_values.push(format!("{} must be greater than 0{}", field, get_source(field)));
+            }
+        }
+        if let Some(rules) = application_configuration_data.rules.as_deref() {
+            invalid_values.extend(
+                validate_status_rules(rules)
+                    .into_iter()
+                    .map(|err| format!("{}{}", err, get_source("rules"))),
+            );
+        }
+        if let Some(working_hours) = application_configuration_data.working_hours.as_ref() {
+            invalid_values.extend(
+                validate_working_hours(working_hours)
+                    .into_iter()
+                    .map(|err| format!("{}{}", err, get_source("working_hours"))),
+            );
+        }
+        if let Some(profile) = application_configuration_data.profile.as_deref() {
+            let profiles = application_configuration_data.profiles.clone().unwrap_or_default();
+            if !profiles.contains_key(profile) {
+                let available_profiles = match profiles.is_empty() {
+                    true => "no profiles are defined".to_string(),
+                    false => format!(
+                        "available profiles: {}",
+                        profiles.keys().cloned().collect::<Vec<String>>().join(", ")
+                    ),
+                };
+                invalid_values.push(format!(
+                    "profile '{}' is not defined in application config files, {}{}",
+                    profile,
+                    available_profiles,
+                    get_source("profile")
+                ));
+            }
+        }
+        // Rules of the selected profile are already validated as merged rules.
+        for (name, profile) in application_configuration_data.profiles.iter().flatten() {
+            if application_configuration_data.profile.as_ref() != Some(name) {
+                invalid_values.extend(
+                    validate_status_rules(profile.rules.as_deref().unwrap_or_default())
+                        .into_iter()
+                        .map(|err| format!("profiles.{}.{}", name, err)),
+                );
+            }
+        }
+        if application_configuration_data.strict_config.unwrap_or(false) {
+            for configuration_layer in &self.configuration_layers {
+                if !configuration_layer.unknown_fields.is_empty() {
+                    invalid_values.push(format!(
+                        "unknown field(s) {} in {} (strict_config is enabled)",
+                        configuration_layer.unknown_fields.join(", "),
+                        configuration_layer.source
+                    ));
+                }
+            }
+        }
+        invalid_values
+    }
+
+    pub fn get_merged_application_configuration_data(
+        &self,
+    ) -> Result<ApplicationConfigurationData, ConfigurationError> {
+        let (merged_application_configuration_data, configuration_sources) =
+            self.get_application_configuration_data_with_sources();
+        self.validate(&merged_application_configuration_data, &configuration_sources)?;
+        Ok(merged_application_configuration_data)
+    }
+
+    // Merged configuration is not validated, so it can be shown even when some values are missing.
+    pub fn get_application_configuration_data_with_sources(
+        &self,
+    ) -> (ApplicationConfigurationData, ConfigurationSources) {
+        let mut merged_application_configuration_data = ApplicationConfigurationData::empty();
+        let mut configuration_sources = ConfigurationSources::new();
+        for configuration_layer in &self.configuration_layers {
+            for (field, value) in configuration_layer.application_configuration_data.get_field_values() {
+                if value.is_some() {
+                    configuration_sources.insert(field.to_string(), configuration_layer.source.clone());
+                }
+            }
+            merged_application_configuration_data.update(configuration_layer.application_configuration_data.clone());
+        }
+        let selected_profile = self
+            .cli_application_configuration_data
+            .profile
+            .as_ref()
+            .or(merged_application_configuration_data.profile.as_ref());
+        if let Some((profile, configuration_source)) =
+            selected_profile.and_then(|selected_profile| self.get_profiles().remove(selected_profile))
+        {
+            let profile_source =
+                ConfigurationSource::Profile(selected_profile.unwrap().clone(), Box::new(configuration_source));
+            if profile.sets_slack_user_oauth_token() {
+                for field in [
+                    "slack_user_oauth_token_path",
+                    "slack_user_oauth_token",
+                    "slack_user_oauth_token_secret",
+                ] {
+                    configuration_sources.remove(field);
+                }
+            }
+            for field in profile.get_set_field_names() {
+                configuration_sources.insert(field.to_string(), profile_source.clone());
+            }
+            merged_application_configuration_data.apply_profile(&profile);
+        }
+        for (field, value) in self.cli_application_configuration_data.get_field_values() {
+            if value.is_some() {
+                let configuration_source = self
+                    .cli_configuration_sources
+                    .get(field)
+                    .cloned()
+                    .unwrap_or(ConfigurationSource::CommandLine(field.to_string()));
+                configuration_sources.insert(field.to_string(), configuration_source);
+            }
+        }
         merged_application_configuration_data.update(self.cli_application_configuration_data.clone());
-        self.validate(&merged_application_configuration_data.clone())?;
-        let service_account_key_path = merged_application_configuration_data.service_account_key_path.clone();
+        (merged_application_configuration_data, configuration_sources)
+    }
 
-        let service_account_key = self.get_service_account_key(service_account_key_path.as_ref().unwrap())?;
+    pub fn get_application_configuration(&self) -> Result<ApplicationConfiguration, ConfigurationError> {
+        let merged_application_configuration_data = self.get_merged_application_configuration_data()?;
+        let service_account_key = match (
+            merged_application_configuration_data.service_account_key.as_ref(),
+            merged_application_configuration_data
+                .service_account_key_secret
+                .as_deref(),
+        ) {
+            (Some(service_account_key_json), _) => {
+                self.get_service_account_key_from_json(service_account_key_json.expose_secret())?
+            }
+            (None, Some(service_account_key_secret)) => {
+                self.get_service_account_key_from_secret(service_account_key_secret)?
+            }
+            (None, None) => self.get_service_account_key(
+                merged_application_configuration_data
+                    .service_account_key_path
+                    .as_ref()
+                    .unwrap(),
+            )?,
+        };
 
-        let slack_user_oauth_token = merged_application_configuration_data
-            .slack_user_oauth_token
-            .clone()
-            .unwrap_or(
+        // Token file is read only when the token is not given directly or via a secret source, so it does not have to
+        // exist in that case.
+        let slack_user_oauth_token = match (
+            merged_application_configuration_data.slack_user_oauth_token.clone(),
+            merged_application_configuration_data
+                .slack_user_oauth_token_secret
+                .as_deref(),
+        ) {
+            (Some(slack_user_oauth_token), _) => slack_user_oauth_token,
+            (None, Some(slack_user_oauth_token_secret)) => {
+                self.get_slack_user_oauth_token_from_secret(slack_user_oauth_token_secret)?
+            }
+            (None, None) => {
                 self.get_slack_user_oauth_token(
                     merged_application_configuration_data
                         .slack_user_oauth_token_path
                         .as_ref()
                         .unwrap(),
                 )?
-                .user_oauth_token,
-            );
+                .user_oauth_token
+            }
+        };
 
         Ok(ApplicationConfiguration {
             calendar_id: merged_application_configuration_data.calendar_id.clone().unwrap(),
-            service_account_key,
+            service_account_key: Secret::new(service_account_key),
             slack_user_oauth_token,
             logging_config_path: merged_application_configuration_data
                 .logging_config_path
                 .clone()
                 .unwrap(),
+            audit_log_path: merged_application_configuration_data.get_audit_log_path(),
+            state_path: merged_application_configuration_data.get_state_path(),
+            rules: merged_application_configuration_data.rules.unwrap_or_default(),
+            working_hours: merged_application_configuration_data.working_hours,
         })
     }
 }
 
-pub fn read_json_configuration<T: DeserializeOwned>(config_path: &String) -> Result<T, ConfigurationError> {
-    let configuration_file = File::open(config_path)?;
-    match serde_json::from_reader(configuration_file) {
-        Ok(configuration) => Ok(configuration),
-        Err(e) => Err(ConfigurationError::DeserializeConfigurationError(
-            config_path.to_string(),
-            e.to_string(),
-        )),
+// Follows XDG base directory specification, falling back to `~/.config` when XDG_CONFIG_HOME is not set.
+pub fn get_user_config_dir() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|config_home| config_home.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_home.join(APPLICATION_NAME))
+}
+
+pub fn find_application_config_file(config_dir: &Path) -> Option<String> {
+    APPLICATION_CONFIG_FILE_EXTENSIONS
+        .iter()
+        .map(|extension| config_dir.join(format!("{}.{}", APPLICATION_CONFIG_FILE_STEM, extension)))
+        .find(|config_path| config_path.exists())
+        .map(|config_path| config_path.display().to_string())
+}
+
+pub fn read_json_configuration<T: DeserializeOwned>(config_path: &str) -> Result<T, ConfigurationError> {
+    read_configuration(config_path, ConfigurationFormat::Json)
+}
+
+pub fn read_configuration<T: DeserializeOwned>(
+    config_path: &str,
+    format: ConfigurationFormat,
+) -> Result<T, ConfigurationError> {
+    let configuration = read_to_string(config_path)?;
+    parse_configuration(&configuration, config_path, format)
+}
+
+// Source is a file path or a name of the environment variable used in error messages.
+pub fn parse_configuration<T: DeserializeOwned>(
+    configuration: &str,
+    source: &str,
+    format: ConfigurationFormat,
+) -> Result<T, ConfigurationError> {
+    match format {
+        ConfigurationFormat::Json => serde_json::from_str(configuration).map_err(|err| {
+            // Line is 0 for errors not caused by the contents, e.g. io errors.
+            let location = (err.line() > 0).then(|| (err.line(), err.column()));
+            get_parse_configuration_error(source, location, err.to_string())
+        }),
+        ConfigurationFormat::Yaml => serde_yaml::from_str(configuration).map_err(|err| {
+            let location = err.location().map(|location| (location.line(), location.column()));
+            get_parse_configuration_error(source, location, err.to_string())
+        }),
+        ConfigurationFormat::Toml => toml::from_str(configuration).map_err(|err| {
+            let location = err.span().map(|span| get_line_and_column(configuration, span.start));
+            get_parse_configuration_error(source, location, err.message().to_string())
+        }),
+    }
+}
+
+fn get_parse_configuration_error(
+    source: &str,
+    location: Option<(usize, usize)>,
+    message: String,
+) -> ConfigurationError {
+    match location {
+        Some((line, column)) => {
+            // Json and yaml errors end with their own location, which is reported separately.
+            let message = message
+                .strip_suffix(&format!(" at line {} column {}", line, column))
+                .unwrap_or(&message)
+                .to_string();
+            ConfigurationError::ParseConfigurationError(source.to_string(), line, column, message)
+        }
+        None => ConfigurationError::DeserializeConfigurationError(source.to_string(), message),
+    }
+}
+
+fn get_line_and_column(text: &str, offset: usize) -> (usize, usize) {
+    let preceding_text = &text[..offset];
+    let line = preceding_text.matches('\n').count() + 1;
+    let column = preceding_text
+        .chars()
+        .rev()
+        .take_while(|character| *character != '\n')
+        .count()
+        + 1;
+    (line, column)
+}
+
+pub fn write_json_configuration<T: Serialize>(
+    config_path: &str,
+    configuration: &T,
+    permissions: u32,
+) -> Result<(), ConfigurationError> {
+    write_configuration(config_path, configuration, ConfigurationFormat::Json, permissions)
+}
+
+pub fn write_configuration<T: Serialize>(
+    config_path: &str,
+    configuration: &T,
+    format: ConfigurationFormat,
+    permissions: u32,
+) -> Result<(), ConfigurationError> {
+    let to_write_configuration_error =
+        |err: std::io::Error| ConfigurationError::WriteConfigurationError(config_path.to_string(), err.to_string());
+    let serialized_configuration = match format {
+        ConfigurationFormat::Json => serde_json::to_string_pretty(configuration).map_err(|err| err.to_string()),
+        ConfigurationFormat::Yaml => serde_yaml::to_string(configuration).map_err(|err| err.to_string()),
+        ConfigurationFormat::Toml => toml::to_string_pretty(configuration).map_err(|err| err.to_string()),
+    }
+    .map_err(|err| ConfigurationError::WriteConfigurationError(config_path.to_string(), err))?;
+
+    if let Some(parent_folder) = Path::new(config_path).parent() {
+        create_dir_all(parent_folder).map_err(to_write_configuration_error)?;
     }
+    let mut configuration_file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(permissions)
+        .open(config_path)
+        .map_err(to_write_configuration_error)?;
+    // Mode is only applied when the file is created, so permissions of an existing file are set explicitly.
+    set_permissions(config_path, Permissions::from_mode(permissions)).map_err(to_write_configuration_error)?;
+    configuration_file
+        .write_all(serialized_configuration.as_bytes())
+        .map_err(to_write_configuration_error)
 }
@@ -0,0 +1,154 @@
+use std::{collections::HashMap, fs::metadata, path::PathBuf, sync::Arc, time::SystemTime};
+
+use arc_swap::ArcSwap;
+use log::info;
+
+use crate::{
+    get_user_config_dir, ApplicationConfiguration, ApplicationConfigurationData, ApplicationConfigurationGetter,
+    ConfigurationError, ConfigurationSources, SYSTEM_CONFIG_DIR,
+};
+
+// Detects changes by polling modification time and size of the files, files which do not exist are watched for
+// creation.
+pub struct ConfigurationFileWatcher {
+    file_states: HashMap<String, Option<(SystemTime, u64)>>,
+}
+
+impl ConfigurationFileWatcher {
+    pub fn new(file_paths: Vec<String>) -> Self {
+        let file_states = file_paths
+            .into_iter()
+            .map(|file_path| {
+                let file_state = Self::get_file_state(&file_path);
+                (file_path, file_state)
+            })
+            .collect();
+        ConfigurationFileWatcher { file_states }
+    }
+
+    fn get_file_state(file_path: &str) -> Option<(SystemTime, u64)> {
+        let file_metadata = metadata(file_path).ok()?;
+        Some((file_metadata.modified().ok()?, file_metadata.len()))
+    }
+
+    pub fn get_changed_file_paths(&mut self) -> Vec<String> {
+        let mut changed_file_paths = Vec::new();
+        for (file_path, file_state) in self.file_states.iter_mut() {
+            let current_file_state = Self::get_file_state(file_path);
+            if current_file_state != *file_state {
+                *file_state = current_file_state;
+                changed_file_paths.push(file_path.clone());
+            }
+        }
+        changed_file_paths.sort();
+        changed_file_paths
+    }
+}
+
+// Holds the current application configuration and replaces it only when the reloaded configuration is valid, so
+// readers always see a complete configuration.
+pub struct ApplicationConfigurationReloader {
+    cli_application_configuration_data: ApplicationConfigurationData,
+    cli_configuration_sources: ConfigurationSources,
+    system_config_dir: Option<PathBuf>,
+    user_config_dir: Option<PathBuf>,
+    application_configuration: ArcSwap<ApplicationConfiguration>,
+    configuration_file_watcher: ConfigurationFileWatcher,
+}
+
+impl ApplicationConfigurationReloader {
+    pub fn new(
+        cli_application_configuration_data: ApplicationConfigurationData,
+        cli_configuration_sources: ConfigurationSources,
+    ) -> Result<Self, ConfigurationError> {
+        Self::new_with_config_dirs(
+            cli_application_configuration_data,
+            cli_configuration_sources,
+            Some(PathBuf::from(SYSTEM_CONFIG_DIR)),
+            get_user_config_dir(),
+        )
+    }
+
+    pub fn new_with_config_dirs(
+        cli_application_configuration_data: ApplicationConfigurationData,
+        cli_configuration_sources: ConfigurationSources,
+        system_config_dir: Option<PathBuf>,
+        user_config_dir: Option<PathBuf>,
+    ) -> Result<Self, ConfigurationError> {
+        let (application_configuration, configuration_file_paths) = Self::load(
+            &cli_application_configuration_data,
+            &cli_configuration_sources,
+            &system_config_dir,
+            &user_config_dir,
+        )?;
+        Ok(ApplicationConfigurationReloader {
+            cli_application_configuration_data,
+            cli_configuration_sources,
+            system_config_dir,
+            user_config_dir,
+            application_configuration: ArcSwap::from_pointee(application_configuration),
+            configuration_file_watcher: ConfigurationFileWatcher::new(configuration_file_paths),
+        })
+    }
+
+    fn load(
+        cli_application_configuration_data: &ApplicationConfigurationData,
+        cli_configuration_sources: &ConfigurationSources,
+        system_config_dir: &Option<PathBuf>,
+        user_config_dir: &Option<PathBuf>,
+    ) -> Result<(ApplicationConfiguration, Vec<String>), ConfigurationError> {
+        let application_configuration_getter = ApplicationConfigurationGetter::new_with_config_dirs(
+            cli_application_configuration_data.clone(),
+            system_config_dir.clone(),
+            user_config_dir.clone(),
+        )?
+        .with_cli_configuration_sources(cli_configuration_sources.clone());
+        let application_configuration = application_configuration_getter.get_application_configuration()?;
+
+        // Secret files are watched too, so a rotated token or key is picked up without a restart.
+        let application_configuration_data =
+            application_configuration_getter.get_merged_application_configuration_data()?;
+        let mut configuration_file_paths = application_configuration_getter.get_configuration_file_paths().to_vec();
+        configuration_file_paths.extend(
+            [
+                application_configuration_data.service_account_key_path,
+                application_configuration_data.slack_user_oauth_token_path,
+            ]
+            .into_iter()
+            .flatten(),
+        );
+        Ok((application_configuration, configuration_file_paths))
+    }
+
+    pub fn get_application_configuration(&self) -> Arc<ApplicationConfiguration> {
+        self.application_configuration.load_full()
+    }
+
+    // On error the current configuration is kept.
+    pub fn reload(&mut self) -> Result<(), ConfigurationError> {
+        let (application_configuration, configuration_file_paths) = Self::load(
+            &self.cli_application_configuration_data,
+            &self.cli_configuration_sources,
+            &self.system_config_dir,
+            &self.user_config_dir,
+        )?;
+        self.application_configuration
+            .store(Arc::new(application_configuration));
+        self.configuration_file_watcher = ConfigurationFileWatcher::new(configuration_file_paths);
+        info!("Successfully reloaded application configuration.");
+        Ok(())
+    }
+
+    // Returns None when no watched file changed since the last check.
+    pub fn reload_if_changed(&mut self) -> Option<Result<(), ConfigurationError>> {
+        let changed_file_paths = self.configuration_file_watcher.get_changed_file_paths();
+        if changed_file_paths.is_empty() {
+            return None;
+        }
+        info!(
+            "Configuration file(s) changed: {}. Reloading application configuration.",
+            changed_file_paths.join(", ")
+        );
+        Some(self.reload())
+    }
+}
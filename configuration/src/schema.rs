@@ -0,0 +1,194 @@
+use clap::{Args, Command};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    validation::{
+        JSON_SCHEMA_FIELD_NAME, SLACK_PROFILE_FIELD_ID_PATTERN, STATUS_EMOJI_PATTERN, WORKING_DAY_HOURS_PATTERN,
+    },
+    ApplicationConfigurationData, ProfileConfigurationData, APPLICATION_NAME, CONFERENCE_PROVIDERS,
+    MAX_STATUS_RULE_OFFSET_MINUTES, STATUS_RULE_EVENT_TYPES,
+};
+
+const BOOLEAN_FIELD_NAMES: [&str; 2] = ["strict_config", "log_to_file"];
+const POSITIVE_INTEGER_FIELD_NAMES: [&str; 2] = ["log_file_size", "log_file_archive_count"];
+
+// Descriptions of the fields are taken from the command line help, so both stay in sync.
+pub fn get_application_configuration_json_schema() -> Value {
+    let command = ApplicationConfigurationData::augment_args(Command::new("application_configuration_data"));
+    let mut properties = Map::new();
+    properties.insert(
+        JSON_SCHEMA_FIELD_NAME.to_string(),
+        json!({
+            "type": "string",
+            "description": "Location of this JSON Schema, used by editors for autocompletion."
+        }),
+    );
+    for arg in command.get_arguments() {
+        let field = arg.get_id().as_str();
+        let mut property = Map::new();
+        if BOOLEAN_FIELD_NAMES.contains(&field) {
+            property.insert("type".to_string(), json!("boolean"));
+        } else if POSITIVE_INTEGER_FIELD_NAMES.contains(&field) {
+            property.insert("type".to_string(), json!("integer"));
+            property.insert("minimum".to_string(), json!(1));
+        } else {
+            property.insert("type".to_string(), json!("string"));
+            let possible_values: Vec<String> = arg
+                .get_possible_values()
+                .iter()
+                .map(|possible_value| possible_value.get_name().to_string())
+                .collect();
+            if !possible_values.is_empty() {
+                property.insert("enum".to_string(), json!(possible_values));
+            }
+        }
+        if let Some(help) = arg.get_help() {
+            property.insert("description".to_string(), json!(help.to_string()));
+        }
+        properties.insert(field.to_string(), Value::Object(property));
+    }
+    let rules_schema = json!({
+            "type": "array",
+            "description": "Slack statuses set while matching calendar events are ongoing. The first matching rule wins.",
+            "items": {
+                "type": "object",
+                "properties": {
+                    "summary_pattern": {
+                        "type": "string",
+                        "format": "regex",
+                        "description": "Regular expression matched against the summary of a calendar event."
+                    },
+                    "status_text": {
+                        "type": "string",
+                        "description": "Slack status text, {return_date} is replaced with the date the user is back."
+                    },
+                    "status_emoji": {
+                        "type": "string",
+                        "pattern": STATUS_EMOJI_PATTERN,
+                        "description": "Slack status emoji code, e.g. :calendar:."
+                    },
+                    "lead_minutes": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "maximum": MAX_STATUS_RULE_OFFSET_MINUTES,
+                        "description": "Minutes before the start of the event when the status is set."
+                    },
+                    "lag_minutes": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "maximum": MAX_STATUS_RULE_OFFSET_MINUTES,
+                        "description": "Minutes after the end of the event when the status is removed."
+                    },
+                    "event_type": {
+                        "type": "string",
+                        "enum": STATUS_RULE_EVENT_TYPES,
+                        "description": "Google Calendar event type matched by the rule, e.g. outOfOffice."
+                    },
+                    "all_day_events": {
+                        "type": "boolean",
+                        "description": "Whether all-day and multi-day all-day events match the rule."
+                    },
+                    "conference_emojis": {
+                        "type": "object",
+                        "propertyNames": {"enum": CONFERENCE_PROVIDERS},
+                        "additionalProperties": {"type": "string", "pattern": STATUS_EMOJI_PATTERN},
+                        "description": "Slack status emoji codes by video conference provider of the event, e.g. zoom."
+                    },
+                    "join_link_field": {
+                        "type": "string",
+                        "pattern": SLACK_PROFILE_FIELD_ID_PATTERN,
+                        "description": "Id of the Slack custom profile field set to the join link of the video call."
+                    }
+                },
+                "required": ["summary_pattern", "status_text"],
+                "additionalProperties": false
+            }
+    });
+    let mut profile_properties = Map::new();
+    for field in ProfileConfigurationData::get_field_names() {
+        let property = match field {
+            "rules" => rules_schema.clone(),
+            _ => properties[field].clone(),
+        };
+        profile_properties.insert(field.to_string(), property);
+    }
+    properties.insert("rules".to_string(), rules_schema);
+    let idle_status_schema = json!({
+        "type": "object",
+        "properties": {
+            "status_text": {
+                "type": "string",
+                "description": "Slack status text."
+            },
+            "status_emoji": {
+                "type": "string",
+                "pattern": STATUS_EMOJI_PATTERN,
+                "description": "Slack status emoji code, e.g. :crescent_moon:."
+            }
+        },
+        "additionalProperties": false
+    });
+    let get_idle_status_schema = |description: &str| {
+        let mut schema = idle_status_schema.clone();
+        schema["description"] = json!(description);
+        schema
+    };
+    properties.insert(
+        "working_hours".to_string(),
+        json!({
+            "type": "object",
+            "description": "Working hours and statuses set when no status rule matches.",
+            "properties": {
+                "days": {
+                    "type": "object",
+                    "description": "Weekday names mapped to working hours, e.g. \"monday\": \"09:00-17:00\". Other days are days off.",
+                    "propertyNames": {
+                        "enum": ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"]
+                    },
+                    "additionalProperties": {
+                        "type": "string",
+                        "pattern": WORKING_DAY_HOURS_PATTERN
+                    }
+                },
+                "time_zone": {
+                    "type": "string",
+                    "description": "IANA time zone of working hours, e.g. Europe/Warsaw. Defaults to the local time zone."
+                },
+                "in_hours_status": get_idle_status_schema("Status during working hours. Empty status clears the Slack status."),
+                "out_of_hours_status": get_idle_status_schema("Status outside of working hours, e.g. Offline."),
+                "working_location_statuses": {
+                    "type": "object",
+                    "description": "Statuses during working hours on days with a working location set in Google Calendar.",
+                    "properties": {
+                        "home": get_idle_status_schema("Status when working from home."),
+                        "office": get_idle_status_schema("Status when working from an office."),
+                        "custom": get_idle_status_schema("Status when working from another location.")
+                    },
+                    "additionalProperties": false
+                }
+            },
+            "required": ["days"],
+            "additionalProperties": false
+        }),
+    );
+    properties.insert(
+        "profiles".to_string(),
+        json!({
+            "type": "object",
+            "description": "Named profiles selected with `profile`, each overriding calendar id, Slack token and rules.",
+            "additionalProperties": {
+                "type": "object",
+                "properties": profile_properties,
+                "additionalProperties": false
+            }
+        }),
+    );
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": format!("{} application configuration", APPLICATION_NAME),
+        "type": "object",
+        "properties": properties,
+        "additionalProperties": false
+    })
+}
@@ -0,0 +1,182 @@
+use std::{collections::HashMap, fs::read_to_string, process::Command};
+
+use log::info;
+
+use crate::{ConfigurationError, Secret};
+
+pub const FILE_SECRET_SCHEME: &str = "file";
+pub const ENV_SECRET_SCHEME: &str = "env";
+pub const COMMAND_SECRET_SCHEME: &str = "cmd";
+pub const KEYRING_SECRET_SCHEME: &str = "keyring";
+
+// A secret source is referenced as `<scheme>:<reference>`, e.g. `cmd:pass show slack-token` or `env:SLACK_TOKEN`.
+// References without a scheme are treated as file paths.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SecretSource {
+    pub scheme: String,
+    pub reference: String,
+}
+
+impl SecretSource {
+    pub fn parse(secret_source: &str) -> SecretSource {
+        match secret_source.split_once(':') {
+            Some((scheme, reference))
+                if scheme.len() > 1 && scheme.chars().all(|character| character.is_ascii_lowercase()) =>
+            {
+                SecretSource {
+                    scheme: scheme.to_string(),
+                    reference: reference.to_string(),
+                }
+            }
+            _ => SecretSource {
+                scheme: FILE_SECRET_SCHEME.to_string(),
+                reference: secret_source.to_string(),
+            },
+        }
+    }
+}
+
+pub trait SecretProvider {
+    fn scheme(&self) -> &str;
+
+    fn get_secret(&self, reference: &str) -> Result<String, String>;
+}
+
+pub struct FileSecretProvider;
+
+impl SecretProvider for FileSecretProvider {
+    fn scheme(&self) -> &str {
+        FILE_SECRET_SCHEME
+    }
+
+    fn get_secret(&self, reference: &str) -> Result<String, String> {
+        read_to_string(reference).map_err(|err| err.to_string())
+    }
+}
+
+pub struct EnvSecretProvider;
+
+impl SecretProvider for EnvSecretProvider {
+    fn scheme(&self) -> &str {
+        ENV_SECRET_SCHEME
+    }
+
+    fn get_secret(&self, reference: &str) -> Result<String, String> {
+        std::env::var(reference).map_err(|err| err.to_string())
+    }
+}
+
+// Runs the command with `sh -c`, so password managers like `pass show slack-token` can be used directly.
+pub struct CommandSecretProvider;
+
+impl SecretProvider for CommandSecretProvider {
+    fn scheme(&self) -> &str {
+        COMMAND_SECRET_SCHEME
+    }
+
+    fn get_secret(&self, reference: &str) -> Result<String, String> {
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg(reference)
+            .output()
+            .map_err(|err| err.to_string())?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(match stderr.trim() {
+                "" => format!("Command exited with {}.", output.status),
+                stderr => format!("Command exited with {}: {}", output.status, stderr),
+            });
+        }
+        String::from_utf8(output.stdout).map_err(|err| err.to_string())
+    }
+}
+
+// Looks the secret up in the Linux Secret Service (e.g. GNOME Keyring, KWallet) with `secret-tool` from libsecret.
+// Reference is `<service>/<account>` matching attributes used when storing the secret:
+// `secret-tool store --label 'slack-gcal-sync' service slack-gcal-sync account slack-token`.
+pub struct KeyringSecretProvider;
+
+impl SecretProvider for KeyringSecretProvider {
+    fn scheme(&self) -> &str {
+        KEYRING_SECRET_SCHEME
+    }
+
+    fn get_secret(&self, reference: &str) -> Result<String, String> {
+        let (service, account) = reference
+            .split_once('/')
+            .ok_or("Keyring secret must be referenced as `keyring:<service>/<account>`.".to_string())?;
+        let output = Command::new("secret-tool")
+            .args(["lookup", "service", service, "account", account])
+            .output()
+            .map_err(|err| format!("Cannot run secret-tool: {}", err))?;
+        if !output.status.success() {
+            return Err(format!(
+                "No secret found in keyring for service '{}' and account '{}'.",
+                service, account
+            ));
+        }
+        String::from_utf8(output.stdout).map_err(|err| err.to_string())
+    }
+}
+
+pub struct SecretResolver {
+    secret_providers: HashMap<String, Box<dyn SecretProvider>>,
+}
+
+impl Default for SecretResolver {
+    fn default() -> Self {
+        SecretResolver::new()
+            .with_secret_provider(Box::new(FileSecretProvider))
+            .with_secret_provider(Box::new(EnvSecretProvider))
+            .with_secret_provider(Box::new(CommandSecretProvider))
+            .with_secret_provider(Box::new(KeyringSecretProvider))
+    }
+}
+
+impl SecretResolver {
+    pub fn new() -> Self {
+        SecretResolver {
+            secret_providers: HashMap::new(),
+        }
+    }
+
+    // Replaces a provider already registered for the same scheme.
+    pub fn with_secret_provider(mut self, secret_provider: Box<dyn SecretProvider>) -> Self {
+        self.secret_providers
+            .insert(secret_provider.scheme().to_string(), secret_provider);
+        self
+    }
+
+    pub fn get_secret(&self, secret_source: &str) -> Result<Secret<String>, ConfigurationError> {
+        let secret_source = SecretSource::parse(secret_source);
+        let secret_provider = self.secret_providers.get(&secret_source.scheme).ok_or_else(|| {
+            ConfigurationError::SecretError(
+                secret_source.scheme.clone(),
+                format!(
+                    "Unknown secret source scheme '{}'. Use one of: {}.",
+                    secret_source.scheme,
+                    self.get_schemes().join(", ")
+                ),
+            )
+        })?;
+        info!("Reading secret from '{}' secret source.", secret_source.scheme);
+        // Errors name only the scheme, as the reference (e.g. a command line) may contain secrets itself.
+        let secret = secret_provider
+            .get_secret(&secret_source.reference)
+            .map_err(|err| ConfigurationError::SecretError(secret_source.scheme.clone(), err))?;
+        let secret = secret.trim_end_matches(['\r', '\n']).to_string();
+        if secret.is_empty() {
+            return Err(ConfigurationError::SecretError(
+                secret_source.scheme,
+                "Secret is empty.".to_string(),
+            ));
+        }
+        Ok(Secret::new(secret))
+    }
+
+    fn get_schemes(&self) -> Vec<String> {
+        let mut schemes: Vec<String> = self.secret_providers.keys().cloned().collect();
+        schemes.sort();
+        schemes
+    }
+}
@@ -0,0 +1,84 @@
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{read_json_configuration, write_json_configuration, ConfigurationError, SECRET_FILE_PERMISSIONS};
+
+// Slack status as set by the application, kept to tell it apart from a status set manually by the user.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SyncedStatus {
+    pub status_text: String,
+    pub status_emoji: String,
+    pub status_expiration: i64,
+}
+
+impl SyncedStatus {
+    // Slack clears an expired status by itself, but the profile may still return it until then.
+    pub fn is_set(&self, now: DateTime<Utc>) -> bool {
+        let is_cleared = self.status_text.is_empty() && self.status_emoji.is_empty();
+        let is_expired = self.status_expiration != 0 && self.status_expiration <= now.timestamp();
+        !is_cleared && !is_expired
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Pause {
+    pub paused_at: DateTime<Utc>,
+    // Paused until resumed, when not given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paused_until: Option<DateTime<Utc>>,
+}
+
+impl Pause {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.paused_until.is_none_or(|paused_until| now < paused_until)
+    }
+}
+
+// Persisted between synchronizations and shared by the daemon and the `pause` and `resume` commands.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct SyncState {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pause: Option<Pause>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_synced_status: Option<SyncedStatus>,
+
+    // Set by `resume --replace-status` until the next status update replaces the status set manually.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub replace_manual_status: bool,
+}
+
+impl SyncState {
+    // Missing state file means the application has not changed anything yet.
+    pub fn read(state_path: &str) -> Result<SyncState, ConfigurationError> {
+        if !Path::new(state_path).exists() {
+            return Ok(SyncState::default());
+        }
+        read_json_configuration(state_path)
+    }
+
+    pub fn write(&self, state_path: &str) -> Result<(), ConfigurationError> {
+        write_json_configuration(state_path, self, SECRET_FILE_PERMISSIONS)
+    }
+
+    pub fn get_active_pause(&self, now: DateTime<Utc>) -> Option<&Pause> {
+        self.pause.as_ref().filter(|pause| pause.is_active(now))
+    }
+
+    // Status differing from the one set last time was changed by the user, who keeps it until it is cleared or expires.
+    // Without a status set by the application before, e.g. after install, any set status was set by the user.
+    pub fn is_manual_status(&self, current_status: &SyncedStatus, now: DateTime<Utc>) -> bool {
+        if self.replace_manual_status || !current_status.is_set(now) {
+            return false;
+        }
+        match &self.last_synced_status {
+            Some(last_synced_status) => {
+                current_status.status_text != last_synced_status.status_text
+                    || current_status.status_emoji != last_synced_status.status_emoji
+            }
+            None => true,
+        }
+    }
+}
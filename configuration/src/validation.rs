@@ -0,0 +1,244 @@
+use std::{fs::File, sync::LazyLock};
+
+use chrono::Weekday;
+use chrono_tz::Tz;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::{
+    parse_working_day_hours, ApplicationConfigurationData, IdleStatus, ProfileConfigurationData, StatusRule,
+    WorkingHours, CONFERENCE_PROVIDERS, MAX_STATUS_RULE_OFFSET_MINUTES, STATUS_RULE_EVENT_TYPES,
+    STATUS_RULE_FIELD_NAMES, STATUS_TEXT_PLACEHOLDERS, WORKING_HOURS_FIELD_NAMES,
+    WORKING_LOCATION_STATUSES_FIELD_NAMES,
+};
+
+// Editors add `$schema` to point at the JSON Schema of the file, it is not a configuration field.
+pub const JSON_SCHEMA_FIELD_NAME: &str = "$schema";
+// One or more Slack emoji codes, e.g. `:calendar:` or `:wave::skin-tone-2:`.
+pub const STATUS_EMOJI_PATTERN: &str = r"^(:[a-z0-9_+'-]+:)+$";
+// Hours of a working day in 24-hour clock, e.g. `09:00-17:00`.
+pub const WORKING_DAY_HOURS_PATTERN: &str = r"^([01][0-9]|2[0-3]):[0-5][0-9]-([01][0-9]|2[0-3]):[0-5][0-9]$";
+const CALENDAR_ID_PATTERN: &str = r"^[^@\s]+@[^@\s]+\.[^@\s]+$";
+// Ids of Slack custom profile fields, e.g. `Xf0123456789`.
+pub const SLACK_PROFILE_FIELD_ID_PATTERN: &str = r"^Xf[A-Z0-9]+$";
+const STATUS_TEXT_PLACEHOLDER_PATTERN: &str = r"\{[^{}]*\}";
+
+static STATUS_EMOJI_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(STATUS_EMOJI_PATTERN).unwrap());
+static CALENDAR_ID_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(CALENDAR_ID_PATTERN).unwrap());
+static SLACK_PROFILE_FIELD_ID_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(SLACK_PROFILE_FIELD_ID_PATTERN).unwrap());
+static STATUS_TEXT_PLACEHOLDER_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(STATUS_TEXT_PLACEHOLDER_PATTERN).unwrap());
+
+pub fn get_unknown_fields(configuration: &Value) -> Vec<String> {
+    let Some(configuration) = configuration.as_object() else {
+        return Vec::new();
+    };
+    let field_names: Vec<&str> = ApplicationConfigurationData::empty()
+        .get_field_values()
+        .into_iter()
+        .map(|(field, _)| field)
+        .collect();
+    let mut unknown_fields = Vec::new();
+    for (field, value) in configuration {
+        if field == JSON_SCHEMA_FIELD_NAME {
+            continue;
+        }
+        if !field_names.contains(&field.as_str()) {
+            unknown_fields.push(field.clone());
+            continue;
+        }
+        match field.as_str() {
+            "rules" => unknown_fields.extend(get_unknown_status_rule_fields(value, field)),
+            "working_hours" => unknown_fields.extend(get_unknown_working_hours_fields(value)),
+            "profiles" => {
+                for (name, profile) in value.as_object().into_iter().flatten() {
+                    for (profile_field, profile_value) in profile.as_object().into_iter().flatten() {
+                        let profile_field_path = format!("profiles.{}.{}", name, profile_field);
+                        if !ProfileConfigurationData::get_field_names().contains(&profile_field.as_str()) {
+                            unknown_fields.push(profile_field_path);
+                        } else if profile_field == "rules" {
+                            unknown_fields.extend(get_unknown_status_rule_fields(profile_value, &profile_field_path));
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    unknown_fields
+}
+
+fn get_unknown_status_rule_fields(rules: &Value, rules_path: &str) -> Vec<String> {
+    let mut unknown_fields = Vec::new();
+    for (index, rule) in rules.as_array().into_iter().flatten().enumerate() {
+        for rule_field in rule.as_object().into_iter().flat_map(|rule| rule.keys()) {
+            if !STATUS_RULE_FIELD_NAMES.contains(&rule_field.as_str()) {
+                unknown_fields.push(format!("{}[{}].{}", rules_path, index, rule_field));
+            }
+        }
+    }
+    unknown_fields
+}
+
+fn get_unknown_working_hours_fields(working_hours: &Value) -> Vec<String> {
+    let mut unknown_fields = Vec::new();
+    for (field, value) in working_hours.as_object().into_iter().flatten() {
+        if !WORKING_HOURS_FIELD_NAMES.contains(&field.as_str()) {
+            unknown_fields.push(format!("working_hours.{}", field));
+        } else if field == "working_location_statuses" {
+            for location in value.as_object().into_iter().flat_map(|value| value.keys()) {
+                if !WORKING_LOCATION_STATUSES_FIELD_NAMES.contains(&location.as_str()) {
+                    unknown_fields.push(format!("working_hours.{}.{}", field, location));
+                }
+            }
+        }
+    }
+    unknown_fields
+}
+
+pub fn validate_calendar_id(calendar_id: &str) -> Result<(), String> {
+    if calendar_id == "primary" || CALENDAR_ID_REGEX.is_match(calendar_id) {
+        return Ok(());
+    }
+    Err(format!(
+        "'{}' is not 'primary' or a calendar id in email format, e.g. my-calendar@gmail.com",
+        calendar_id
+    ))
+}
+
+pub fn validate_readable_file(path: &str) -> Result<(), String> {
+    let file = File::open(path).map_err(|err| format!("'{}' cannot be read: {}", path, err))?;
+    match file.metadata() {
+        Ok(metadata) if metadata.is_file() => Ok(()),
+        Ok(_) => Err(format!("'{}' is not a file", path)),
+        Err(err) => Err(format!("'{}' cannot be read: {}", path, err)),
+    }
+}
+
+pub fn validate_status_emoji(status_emoji: &str) -> Result<(), String> {
+    if status_emoji.is_empty() || STATUS_EMOJI_REGEX.is_match(status_emoji) {
+        return Ok(());
+    }
+    Err(format!("'{}' is not a Slack emoji code, e.g. :calendar:", status_emoji))
+}
+
+pub fn validate_status_rules(rules: &[StatusRule]) -> Vec<String> {
+    let mut invalid_values = Vec::new();
+    for (index, rule) in rules.iter().enumerate() {
+        if let Err(err) = rule.summary_pattern.get_regex() {
+            invalid_values.push(format!(
+                "rules[{}].summary_pattern '{}' is not a valid regular expression: {}",
+                index,
+                rule.summary_pattern,
+                // Regex errors span several lines pointing at the invalid part of the pattern.
+                err.to_string()
+                    .lines()
+                    .last()
+                    .unwrap_or_default()
+                    .trim_start_matches("error: ")
+            ));
+        }
+        if let Err(err) = validate_status_emoji(&rule.status_emoji) {
+            invalid_values.push(format!("rules[{}].status_emoji {}", index, err));
+        }
+        for (field, minutes) in [("lead_minutes", rule.lead_minutes), ("lag_minutes", rule.lag_minutes)] {
+            if minutes > MAX_STATUS_RULE_OFFSET_MINUTES {
+                invalid_values.push(format!(
+                    "rules[{}].{} {} is longer than a day, at most {} minutes are allowed",
+                    index, field, minutes, MAX_STATUS_RULE_OFFSET_MINUTES
+                ));
+            }
+        }
+        if let Some(event_type) = rule.event_type.as_deref() {
+            if !STATUS_RULE_EVENT_TYPES.contains(&event_type) {
+                invalid_values.push(format!(
+                    "rules[{}].event_type '{}' is not one of: {}",
+                    index,
+                    event_type,
+                    STATUS_RULE_EVENT_TYPES.join(", ")
+                ));
+            }
+        }
+        for placeholder in STATUS_TEXT_PLACEHOLDER_REGEX
+            .find_iter(&rule.status_text)
+            .map(|placeholder| placeholder.as_str())
+            .filter(|placeholder| !STATUS_TEXT_PLACEHOLDERS.contains(placeholder))
+        {
+            invalid_values.push(format!(
+                "rules[{}].status_text placeholder '{}' is not one of: {}",
+                index,
+                placeholder,
+                STATUS_TEXT_PLACEHOLDERS.join(", ")
+            ));
+        }
+        for (conference_provider, status_emoji) in &rule.conference_emojis {
+            if !CONFERENCE_PROVIDERS.contains(&conference_provider.as_str()) {
+                invalid_values.push(format!(
+                    "rules[{}].conference_emojis.{} is not one of: {}",
+                    index,
+                    conference_provider,
+                    CONFERENCE_PROVIDERS.join(", ")
+                ));
+            }
+            if let Err(err) = validate_status_emoji(status_emoji) {
+                invalid_values.push(format!(
+                    "rules[{}].conference_emojis.{} {}",
+                    index, conference_provider, err
+                ));
+            }
+        }
+        if let Some(join_link_field) = rule.join_link_field.as_deref() {
+            if !SLACK_PROFILE_FIELD_ID_REGEX.is_match(join_link_field) {
+                invalid_values.push(format!(
+                    "rules[{}].join_link_field '{}' is not a Slack custom profile field id, e.g. Xf0123456789",
+                    index, join_link_field
+                ));
+            }
+        }
+    }
+    invalid_values
+}
+
+pub fn validate_working_hours(working_hours: &WorkingHours) -> Vec<String> {
+    let mut invalid_values = Vec::new();
+    for (day, hours) in &working_hours.days {
+        if day.parse::<Weekday>().is_err() {
+            invalid_values.push(format!("working_hours.days.{} is not a weekday, e.g. monday", day));
+        }
+        if let Err(err) = parse_working_day_hours(hours) {
+            invalid_values.push(format!("working_hours.days.{} {}", day, err));
+        }
+    }
+    if let Some(time_zone) = working_hours.time_zone.as_deref() {
+        if time_zone.parse::<Tz>().is_err() {
+            invalid_values.push(format!(
+                "working_hours.time_zone '{}' is not an IANA time zone, e.g. Europe/Warsaw",
+                time_zone
+            ));
+        }
+    }
+    let working_location_statuses = &working_hours.working_location_statuses;
+    let idle_statuses: [(&str, Option<&IdleStatus>); 5] = [
+        ("in_hours_status", Some(&working_hours.in_hours_status)),
+        ("out_of_hours_status", Some(&working_hours.out_of_hours_status)),
+        (
+            "working_location_statuses.home",
+            working_location_statuses.home.as_ref(),
+        ),
+        (
+            "working_location_statuses.office",
+            working_location_statuses.office.as_ref(),
+        ),
+        (
+            "working_location_statuses.custom",
+            working_location_statuses.custom.as_ref(),
+        ),
+    ];
+    for (field, idle_status) in idle_statuses {
+        if let Some(Err(err)) = idle_status.map(|idle_status| validate_status_emoji(&idle_status.status_emoji)) {
+            invalid_values.push(format!("working_hours.{}.status_emoji {}", field, err));
+        }
+    }
+    invalid_values
+}
@@ -0,0 +1,79 @@
+use std::collections::BTreeMap;
+
+use chrono::{NaiveTime, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+pub const WORKING_HOURS_FIELD_NAMES: [&str; 5] = [
+    "days",
+    "time_zone",
+    "in_hours_status",
+    "out_of_hours_status",
+    "working_location_statuses",
+];
+pub const WORKING_LOCATION_STATUSES_FIELD_NAMES: [&str; 3] = ["home", "office", "custom"];
+const WORKING_HOURS_TIME_FORMAT: &str = "%H:%M";
+
+// Status set when no status rule matches. Empty status clears the Slack status.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct IdleStatus {
+    #[serde(default)]
+    pub status_text: String,
+    #[serde(default)]
+    pub status_emoji: String,
+}
+
+// Statuses used during working hours when a `workingLocation` event says where the user works today.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct WorkingLocationStatuses {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub home: Option<IdleStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub office: Option<IdleStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom: Option<IdleStatus>,
+}
+
+// Working hours are in their time zone, or in the local time zone of the application, which follows the `TZ` environment
+// variable, when it is not set.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct WorkingHours {
+    // Weekday names, e.g. `monday`, mapped to hours, e.g. `09:00-17:00`. Other days are days off.
+    pub days: BTreeMap<String, String>,
+    // IANA time zone name, e.g. `Europe/Warsaw`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<String>,
+    #[serde(default)]
+    pub in_hours_status: IdleStatus,
+    #[serde(default)]
+    pub out_of_hours_status: IdleStatus,
+    #[serde(default)]
+    pub working_location_statuses: WorkingLocationStatuses,
+}
+
+impl WorkingHours {
+    // Days are validated when configuration is loaded, invalid hours are treated as a day off.
+    pub fn get_hours(&self, weekday: Weekday) -> Option<(NaiveTime, NaiveTime)> {
+        self.days
+            .iter()
+            .find(|(day, _)| day.parse::<Weekday>() == Ok(weekday))
+            .and_then(|(_, hours)| parse_working_day_hours(hours).ok())
+    }
+
+    // Time zone is validated when configuration is loaded, an invalid time zone is treated as not set.
+    pub fn get_time_zone(&self) -> Option<Tz> {
+        self.time_zone.as_deref().and_then(|time_zone| time_zone.parse().ok())
+    }
+}
+
+pub fn parse_working_day_hours(hours: &str) -> Result<(NaiveTime, NaiveTime), String> {
+    let invalid_hours_error = || format!("'{}' is not working hours, e.g. 09:00-17:00", hours);
+    let (start, end) = hours.split_once('-').ok_or_else(invalid_hours_error)?;
+    let start =
+        NaiveTime::parse_from_str(start.trim(), WORKING_HOURS_TIME_FORMAT).map_err(|_| invalid_hours_error())?;
+    let end = NaiveTime::parse_from_str(end.trim(), WORKING_HOURS_TIME_FORMAT).map_err(|_| invalid_hours_error())?;
+    if start >= end {
+        return Err(format!("'{}' must start before it ends", hours));
+    }
+    Ok((start, end))
+}
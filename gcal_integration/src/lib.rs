@@ -1,16 +1,21 @@
 mod tests;
 
-use chrono::{Datelike, Duration, TimeZone, Utc};
+use std::sync::LazyLock;
+
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc};
 use google_calendar3::{
-    api::Event,
+    api::{Event, Scope},
     hyper_rustls,
     hyper_rustls::HttpsConnector,
     hyper_util,
     hyper_util::client::legacy::connect::HttpConnector,
-    yup_oauth2::{authenticator::Authenticator, ServiceAccountAuthenticator, ServiceAccountKey},
+    yup_oauth2::{
+        authenticator::Authenticator, Error as AuthenticationError, ServiceAccountAuthenticator, ServiceAccountKey,
+    },
     CalendarHub, Error as GoogleAPIError,
 };
 use log::info;
+use regex::Regex;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -18,8 +23,17 @@ pub enum GoogleCalendarIntegrationError {
     #[error("Cannot communicate with Google Calendar API")]
     GoogleCalendarCommunicationError(#[from] Box<GoogleAPIError>),
 
+    #[error("Failed to get access token for the service account: {0}")]
+    AccessTokenError(#[from] AuthenticationError),
+
     #[error("IO Error occurred")]
     IOError(#[from] std::io::Error),
+
+    #[error("Calendar '{0}' was not found or it is not shared with the service account.")]
+    CalendarNotFoundError(String),
+
+    #[error("Access to calendar '{0}' is forbidden: {1}")]
+    CalendarAccessForbiddenError(String, String),
 }
 
 impl From<GoogleAPIError> for GoogleCalendarIntegrationError {
@@ -28,6 +42,22 @@ impl From<GoogleAPIError> for GoogleCalendarIntegrationError {
     }
 }
 
+fn get_calendar_access_error(err: GoogleAPIError, calendar_id: &str) -> GoogleCalendarIntegrationError {
+    let (status_code, message) = match &err {
+        GoogleAPIError::BadRequest(value) => (
+            value["error"]["code"].as_u64(),
+            value["error"]["message"].as_str().unwrap_or_default().to_string(),
+        ),
+        GoogleAPIError::Failure(response) => (Some(response.status().as_u16() as u64), String::new()),
+        _ => (None, String::new()),
+    };
+    match status_code {
+        Some(404) => GoogleCalendarIntegrationError::CalendarNotFoundError(calendar_id.to_string()),
+        Some(403) => GoogleCalendarIntegrationError::CalendarAccessForbiddenError(calendar_id.to_string(), message),
+        _ => err.into(),
+    }
+}
+
 pub fn get_calendar_hub(
     authenticator: Authenticator<HttpsConnector<HttpConnector>>,
 ) -> Result<CalendarHub<HttpsConnector<HttpConnector>>, GoogleCalendarIntegrationError> {
@@ -54,21 +84,200 @@ pub async fn get_service_account_authenticator(
     Ok(service_account_authenticator)
 }
 
+// Tokens are cached by the authenticator, so the calendar hub built with it reuses the token.
+pub async fn check_service_account_access_token(
+    authenticator: &Authenticator<HttpsConnector<HttpConnector>>,
+) -> Result<(), GoogleCalendarIntegrationError> {
+    authenticator.token(&[Scope::EventReadonly]).await?;
+    Ok(())
+}
+
+// Time skipped by a daylight saving time change falls back to the same time in UTC.
+pub fn get_date_time_in_time_zone<Tz: TimeZone>(date: NaiveDate, time: NaiveTime, time_zone: &Tz) -> DateTime<Utc> {
+    let local_date_time = date.and_time(time);
+    time_zone
+        .from_local_datetime(&local_date_time)
+        .earliest()
+        .map(|date_time| date_time.with_timezone(&Utc))
+        .unwrap_or(local_date_time.and_utc())
+}
+
+// Local time zone follows the `TZ` environment variable.
+pub fn get_local_date_time(date: NaiveDate, time: NaiveTime) -> DateTime<Utc> {
+    get_date_time_in_time_zone(date, time, &Local)
+}
+
+pub fn get_start_of_local_day(date: NaiveDate) -> DateTime<Utc> {
+    get_local_date_time(date, NaiveTime::MIN)
+}
+
+// Recurring events are expanded into their instances, otherwise the first event of the series is returned instead of
+// today's instance. Instances can only be ordered by start time.
 pub async fn get_calendar_events_for_today(
     hub: CalendarHub<HttpsConnector<HttpConnector>>,
     calendar_id: &str,
 ) -> Result<Vec<Event>, GoogleCalendarIntegrationError> {
-    let now = Utc::now();
-    let start_of_day = Utc
-        .with_ymd_and_hms(now.year(), now.month(), now.day(), 0, 0, 0)
-        .unwrap();
-    let end_of_day = start_of_day + Duration::days(1);
+    let today = Local::now().date_naive();
+    let start_of_day = get_start_of_local_day(today);
+    let end_of_day = get_start_of_local_day(today + Duration::days(1));
     let (_, events) = hub
         .events()
         .list(calendar_id)
         .time_min(start_of_day)
         .time_max(end_of_day)
+        .single_events(true)
+        .order_by(START_TIME_EVENTS_ORDER)
         .doit()
         .await?;
     Ok(events.items.unwrap_or_else(Vec::new))
 }
+
+pub const CANCELLED_EVENT_STATUS: &str = "cancelled";
+pub const START_TIME_EVENTS_ORDER: &str = "startTime";
+pub const WORKING_LOCATION_EVENT_TYPE: &str = "workingLocation";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WorkingLocation {
+    Home,
+    Office,
+    Custom,
+}
+
+// Working location events say where the user works, they are not meetings.
+pub fn get_working_location(event: &Event) -> Option<WorkingLocation> {
+    if event.event_type.as_deref() != Some(WORKING_LOCATION_EVENT_TYPE) {
+        return None;
+    }
+    let working_location_properties = event.working_location_properties.as_ref()?;
+    match working_location_properties.type_.as_deref() {
+        Some("homeOffice") => Some(WorkingLocation::Home),
+        Some("officeLocation") => Some(WorkingLocation::Office),
+        Some("customLocation") => Some(WorkingLocation::Custom),
+        _ if working_location_properties.home_office.is_some() => Some(WorkingLocation::Home),
+        _ if working_location_properties.office_location.is_some() => Some(WorkingLocation::Office),
+        _ if working_location_properties.custom_location.is_some() => Some(WorkingLocation::Custom),
+        _ => None,
+    }
+}
+
+pub const VIDEO_ENTRY_POINT_TYPE: &str = "video";
+// Join links end at whitespace, quotes or tags, as descriptions of events may contain HTML.
+static CONFERENCE_JOIN_URL_REGEXES: LazyLock<[(ConferenceProvider, Regex); 3]> = LazyLock::new(|| {
+    [
+        (
+            ConferenceProvider::Meet,
+            Regex::new(r"https://meet\.google\.com/[a-z]+-[a-z]+-[a-z]+").unwrap(),
+        ),
+        (
+            ConferenceProvider::Zoom,
+            Regex::new(r#"https://([a-zA-Z0-9-]+\.)?zoom\.us/(j|my|w)/[^\s"'<>]+"#).unwrap(),
+        ),
+        (
+            ConferenceProvider::Teams,
+            Regex::new(r#"https://teams\.(microsoft|live)\.com/(l/meetup-join|meet)/[^\s"'<>]+"#).unwrap(),
+        ),
+    ]
+});
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConferenceProvider {
+    Meet,
+    Zoom,
+    Teams,
+}
+
+impl ConferenceProvider {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            ConferenceProvider::Meet => "meet",
+            ConferenceProvider::Zoom => "zoom",
+            ConferenceProvider::Teams => "teams",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conference {
+    pub provider: ConferenceProvider,
+    pub join_url: String,
+}
+
+fn find_conference(text: &str) -> Option<Conference> {
+    CONFERENCE_JOIN_URL_REGEXES
+        .iter()
+        .filter_map(|(provider, join_url_regex)| {
+            join_url_regex
+                .find(text)
+                .map(|join_url| (join_url.start(), *provider, join_url.as_str()))
+        })
+        .min_by_key(|(start, _, _)| *start)
+        .map(|(_, provider, join_url)| Conference {
+            provider,
+            join_url: join_url.to_string(),
+        })
+}
+
+// Video entry point of the conference data is added by Google Meet or a conferencing add-on, e.g. Zoom for Google
+// Workspace. Otherwise the first join link pasted into the location or the description of the event is used.
+pub fn get_conference(event: &Event) -> Option<Conference> {
+    let video_entry_point_uri = event
+        .conference_data
+        .as_ref()
+        .and_then(|conference_data| conference_data.entry_points.as_ref())
+        .and_then(|entry_points| {
+            entry_points
+                .iter()
+                .find(|entry_point| entry_point.entry_point_type.as_deref() == Some(VIDEO_ENTRY_POINT_TYPE))
+        })
+        .and_then(|entry_point| entry_point.uri.as_deref());
+    [
+        video_entry_point_uri,
+        event.location.as_deref(),
+        event.description.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .find_map(find_conference)
+}
+
+// All-day events have dates of the first day and the day after the last day.
+pub fn get_all_day_event_dates(event: &Event) -> Option<(NaiveDate, NaiveDate)> {
+    let start = event.start.as_ref().and_then(|start| start.date)?;
+    let end = event.end.as_ref().and_then(|end| end.date)?;
+    Some((start, end))
+}
+
+// All-day events have a date instead of a date time and have no time range.
+pub fn get_event_time_range(event: &Event) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let start = event.start.as_ref().and_then(|start| start.date_time)?;
+    let end = event.end.as_ref().and_then(|end| end.date_time)?;
+    Some((start, end))
+}
+
+// All-day events, also lasting several days, last from midnight of the first day to midnight of the day after the last
+// day in the local time zone.
+pub fn get_event_local_time_range(event: &Event) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    get_event_time_range(event).or_else(|| {
+        get_all_day_event_dates(event).map(|(start, end)| (get_start_of_local_day(start), get_start_of_local_day(end)))
+    })
+}
+
+pub async fn get_calendar_access_role(
+    hub: &CalendarHub<HttpsConnector<HttpConnector>>,
+    calendar_id: &str,
+) -> Result<String, GoogleCalendarIntegrationError> {
+    info!("Checking access to google calendar: '{}'.", calendar_id);
+    let (_, events) = hub
+        .events()
+        .list(calendar_id)
+        .max_results(1)
+        .doit()
+        .await
+        .map_err(|err| get_calendar_access_error(err, calendar_id))?;
+    let access_role = events.access_role.unwrap_or_else(|| "none".to_string());
+    info!(
+        "Successfully checked access to google calendar: '{}'. Access role: '{}'.",
+        calendar_id, access_role
+    );
+    Ok(access_role)
+}
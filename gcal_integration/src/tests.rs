@@ -1,6 +1,12 @@
 #[cfg(test)]
 mod test_utils {
+    use google_calendar3::{
+        hyper_rustls::HttpsConnector,
+        hyper_util::client::legacy::connect::HttpConnector,
+        yup_oauth2::{authenticator::Authenticator, ServiceAccountAuthenticator, ServiceAccountKey},
+    };
     use mockito::{Mock, ServerGuard};
+    use rsa::{pkcs8::EncodePrivateKey, rand_core::OsRng, RsaPrivateKey};
 
     pub fn setup_default_crypto_provider() {
         let _ = rustls::crypto::ring::default_provider().install_default();
@@ -22,30 +28,54 @@ mod test_utils {
 
         (mock_auth_api, mock_auth_server)
     }
+
+    pub async fn get_mock_service_account_authenticator(
+        mock_auth_server_url: &str,
+    ) -> Authenticator<HttpsConnector<HttpConnector>> {
+        let private_key_pem = RsaPrivateKey::new(&mut OsRng, 2048)
+            .unwrap()
+            .to_pkcs8_pem(Default::default())
+            .unwrap();
+        let service_account_key = ServiceAccountKey {
+            key_type: Some("service_account".to_string()),
+            project_id: Some("blah".to_string()),
+            private_key_id: Some("fake_private_key_id".to_string()),
+            private_key: private_key_pem.to_string(),
+            client_email: "blah@bob.com".to_string(),
+            client_id: Some("fake_client_id".to_string()),
+            auth_uri: Some(mock_auth_server_url.to_string()),
+            token_uri: mock_auth_server_url.to_string(),
+            auth_provider_x509_cert_url: Some(mock_auth_server_url.to_string()),
+            client_x509_cert_url: Some(mock_auth_server_url.to_string()),
+        };
+
+        ServiceAccountAuthenticator::builder(service_account_key)
+            .build()
+            .await
+            .unwrap()
+    }
 }
 
 #[cfg(test)]
 mod test_get_calendar_events_for_today {
-    use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
-    use google_calendar3::{
-        hyper_rustls::HttpsConnector,
-        hyper_util::client::legacy::connect::HttpConnector,
-        yup_oauth2::{authenticator::Authenticator, ServiceAccountAuthenticator, ServiceAccountKey},
-    };
+    use chrono::{DateTime, Duration, Local, Utc};
     use mockito::{Mock, ServerGuard};
-    use rsa::{pkcs8::EncodePrivateKey, rand_core::OsRng, RsaPrivateKey};
 
-    use super::test_utils::{get_mock_auth_setup, setup_default_crypto_provider};
-    use crate::{get_calendar_events_for_today, get_calendar_hub};
+    use super::test_utils::{
+        get_mock_auth_setup, get_mock_service_account_authenticator, setup_default_crypto_provider,
+    };
+    use crate::{get_calendar_events_for_today, get_calendar_hub, get_start_of_local_day};
 
     fn get_google_api_formatted_date(date: DateTime<Utc>) -> String {
         date.to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
     }
 
-    fn get_start_of_day() -> DateTime<Utc> {
-        let now = Utc::now();
-        Utc.with_ymd_and_hms(now.year(), now.month(), now.day(), 0, 0, 0)
-            .unwrap()
+    fn get_start_and_end_of_day() -> (DateTime<Utc>, DateTime<Utc>) {
+        let today = Local::now().date_naive();
+        (
+            get_start_of_local_day(today),
+            get_start_of_local_day(today + Duration::days(1)),
+        )
     }
 
     async fn get_mock_google_api_setup(
@@ -59,8 +89,10 @@ mod test_get_calendar_events_for_today {
         let mock_google_api = mock_google_api_server
             .mock("GET", format!("/calendars/{}/events", calendar_id).as_str())
             .match_query(mockito::Matcher::AllOf(vec![
-                mockito::Matcher::UrlEncoded("timeMin".into(), get_google_api_formatted_date(start_of_day).into()),
-                mockito::Matcher::UrlEncoded("timeMax".into(), get_google_api_formatted_date(end_of_day).into()),
+                mockito::Matcher::UrlEncoded("timeMin".into(), get_google_api_formatted_date(start_of_day)),
+                mockito::Matcher::UrlEncoded("timeMax".into(), get_google_api_formatted_date(end_of_day)),
+                mockito::Matcher::UrlEncoded("singleEvents".into(), "true".into()),
+                mockito::Matcher::UrlEncoded("orderBy".into(), "startTime".into()),
                 mockito::Matcher::UrlEncoded("alt".into(), "json".into()),
             ]))
             .with_body(response_body)
@@ -70,37 +102,10 @@ mod test_get_calendar_events_for_today {
         (mock_google_api, mock_google_api_server)
     }
 
-    async fn get_mock_service_account_authenticator(
-        mock_auth_server_url: &str,
-    ) -> Authenticator<HttpsConnector<HttpConnector>> {
-        let private_key_pem = RsaPrivateKey::new(&mut OsRng, 2048)
-            .unwrap()
-            .to_pkcs8_pem(Default::default())
-            .unwrap();
-        let service_account_key = ServiceAccountKey {
-            key_type: Some("service_account".to_string()),
-            project_id: Some("blah".to_string()),
-            private_key_id: Some("fake_private_key_id".to_string()),
-            private_key: private_key_pem.to_string(),
-            client_email: "blah@bob.com".to_string(),
-            client_id: Some("fake_client_id".to_string()),
-            auth_uri: Some(mock_auth_server_url.to_string()),
-            token_uri: mock_auth_server_url.to_string(),
-            auth_provider_x509_cert_url: Some(mock_auth_server_url.to_string()),
-            client_x509_cert_url: Some(mock_auth_server_url.to_string()),
-        };
-
-        ServiceAccountAuthenticator::builder(service_account_key)
-            .build()
-            .await
-            .unwrap()
-    }
-
     #[tokio::test]
     async fn it_returns_empty_list_of_events_if_none_are_present() {
         let _ = env_logger::try_init();
-        let start_of_day = get_start_of_day();
-        let end_of_day = start_of_day + Duration::days(1);
+        let (start_of_day, end_of_day) = get_start_and_end_of_day();
         let calendar_id = "test_calendar_id";
         let mock_google_api_response = serde_json::json!({
             "items": []
@@ -132,8 +137,7 @@ mod test_get_calendar_events_for_today {
     #[tokio::test]
     async fn it_returns_calendar_events_for_today() {
         let _ = env_logger::try_init();
-        let start_of_day = get_start_of_day();
-        let end_of_day = start_of_day + Duration::days(1);
+        let (start_of_day, end_of_day) = get_start_and_end_of_day();
 
         let calendar_id = "test_calendar_id";
         let mock_google_api_response = serde_json::json!({
@@ -189,6 +193,115 @@ mod test_get_calendar_events_for_today {
     }
 }
 
+#[cfg(test)]
+mod test_get_calendar_access_role {
+    use mockito::{Mock, ServerGuard};
+    use serde_json::Value;
+
+    use super::test_utils::{
+        get_mock_auth_setup, get_mock_service_account_authenticator, setup_default_crypto_provider,
+    };
+    use crate::{get_calendar_access_role, get_calendar_hub, GoogleCalendarIntegrationError};
+
+    async fn get_mock_google_api_setup(response_body: &Value, status: usize, calendar_id: &str) -> (Mock, ServerGuard) {
+        let mut mock_google_api_server = mockito::Server::new_async().await;
+
+        let mock_google_api = mock_google_api_server
+            .mock("GET", format!("/calendars/{}/events", calendar_id).as_str())
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("maxResults".into(), "1".into()),
+                mockito::Matcher::UrlEncoded("alt".into(), "json".into()),
+            ]))
+            .with_body(response_body.to_string())
+            .with_status(status)
+            .create_async()
+            .await;
+        (mock_google_api, mock_google_api_server)
+    }
+
+    async fn get_calendar_access_role_from_mock_google_api(
+        response_body: &Value,
+        status: usize,
+        calendar_id: &str,
+    ) -> Result<String, GoogleCalendarIntegrationError> {
+        let (mock_google_api, mock_google_api_server) =
+            get_mock_google_api_setup(response_body, status, calendar_id).await;
+
+        let (_mock_auth_api, mock_auth_server) = get_mock_auth_setup().await;
+        let mock_service_account_authenticator =
+            get_mock_service_account_authenticator(mock_auth_server.url().as_str()).await;
+
+        // Required per test to avoid this error:
+        // no process-level CryptoProvider available -- call CryptoProvider::install_default() before this point
+        setup_default_crypto_provider();
+        let mut hub = get_calendar_hub(mock_service_account_authenticator).unwrap();
+        hub.base_url(mock_google_api_server.url() + "/");
+
+        let access_role = get_calendar_access_role(&hub, calendar_id).await;
+        mock_google_api.assert_async().await;
+        access_role
+    }
+
+    #[tokio::test]
+    async fn it_returns_access_role_of_shared_calendar() {
+        let _ = env_logger::try_init();
+        let response_body = serde_json::json!({
+            "accessRole": "reader",
+            "items": []
+        });
+
+        let access_role = get_calendar_access_role_from_mock_google_api(&response_body, 200, "test_calendar_id")
+            .await
+            .unwrap();
+
+        assert_eq!(access_role, "reader");
+    }
+
+    #[tokio::test]
+    async fn it_raises_calendar_not_found_error_on_404() {
+        let _ = env_logger::try_init();
+        let response_body = serde_json::json!({
+            "error": {
+                "code": 404,
+                "message": "Not Found",
+            }
+        });
+
+        let error = get_calendar_access_role_from_mock_google_api(&response_body, 404, "test_calendar_id").await;
+
+        assert!(matches!(
+            error,
+            Err(GoogleCalendarIntegrationError::CalendarNotFoundError { .. })
+        ));
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "Calendar 'test_calendar_id' was not found or it is not shared with the service account."
+        );
+    }
+
+    #[tokio::test]
+    async fn it_raises_calendar_access_forbidden_error_on_403() {
+        let _ = env_logger::try_init();
+        let response_body = serde_json::json!({
+            "error": {
+                "code": 403,
+                "message": "Google Calendar API has not been used in project 123 before or it is disabled.",
+            }
+        });
+
+        let error = get_calendar_access_role_from_mock_google_api(&response_body, 403, "test_calendar_id").await;
+
+        assert!(matches!(
+            error,
+            Err(GoogleCalendarIntegrationError::CalendarAccessForbiddenError { .. })
+        ));
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "Access to calendar 'test_calendar_id' is forbidden: Google Calendar API has not been used in project 123 before or it is disabled."
+        );
+    }
+}
+
 #[cfg(test)]
 mod test_get_service_account_authenticator {
     use google_calendar3::yup_oauth2::ServiceAccountKey;
@@ -260,3 +373,273 @@ mod test_error_conversion {
         }
     }
 }
+
+#[cfg(test)]
+mod test_check_service_account_access_token {
+    use super::test_utils::{get_mock_service_account_authenticator, setup_default_crypto_provider};
+    use crate::{check_service_account_access_token, GoogleCalendarIntegrationError};
+
+    #[tokio::test]
+    async fn it_gets_access_token_for_service_account() {
+        let _ = env_logger::try_init();
+        let mut mock_auth_server = mockito::Server::new_async().await;
+        let mock_auth_api = mock_auth_server
+            .mock("POST", mockito::Matcher::Any)
+            .with_body(
+                serde_json::json!({"access_token": "token", "token_type": "Bearer", "expires_in": 3600}).to_string(),
+            )
+            .with_status(200)
+            .create_async()
+            .await;
+        setup_default_crypto_provider();
+        let mock_service_account_authenticator =
+            get_mock_service_account_authenticator(mock_auth_server.url().as_str()).await;
+
+        check_service_account_access_token(&mock_service_account_authenticator)
+            .await
+            .unwrap();
+        mock_auth_api.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn it_raises_access_token_error_when_token_is_refused() {
+        let _ = env_logger::try_init();
+        let mut mock_auth_server = mockito::Server::new_async().await;
+        let _mock_auth_api = mock_auth_server
+            .mock("POST", mockito::Matcher::Any)
+            .with_body(serde_json::json!({"error": "invalid_grant"}).to_string())
+            .with_status(400)
+            .create_async()
+            .await;
+        setup_default_crypto_provider();
+        let mock_service_account_authenticator =
+            get_mock_service_account_authenticator(mock_auth_server.url().as_str()).await;
+
+        let result = check_service_account_access_token(&mock_service_account_authenticator).await;
+
+        assert!(matches!(
+            result,
+            Err(GoogleCalendarIntegrationError::AccessTokenError(..))
+        ));
+    }
+}
+
+#[cfg(test)]
+mod test_get_event_time_range {
+    use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+    use google_calendar3::api::{Event, EventDateTime};
+
+    use crate::{get_event_local_time_range, get_event_time_range, get_start_of_local_day};
+
+    fn get_event(start: DateTime<Utc>, end: DateTime<Utc>) -> Event {
+        Event {
+            start: Some(EventDateTime {
+                date_time: Some(start),
+                ..Default::default()
+            }),
+            end: Some(EventDateTime {
+                date_time: Some(end),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn it_returns_time_range_of_timed_events_only() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 6, 15, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2025, 1, 6, 16, 0, 0).unwrap();
+        let event_without_end = Event {
+            end: None,
+            ..get_event(start, end)
+        };
+
+        assert_eq!(get_event_time_range(&get_event(start, end)), Some((start, end)));
+        assert_eq!(get_event_time_range(&event_without_end), None);
+    }
+
+    #[test]
+    fn it_returns_local_time_range_of_timed_and_multi_day_all_day_events() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 6, 15, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2025, 1, 6, 16, 0, 0).unwrap();
+        let first_day = NaiveDate::from_ymd_opt(2025, 1, 6).unwrap();
+        let return_day = NaiveDate::from_ymd_opt(2025, 1, 13).unwrap();
+        let vacation = Event {
+            start: Some(EventDateTime {
+                date: Some(first_day),
+                ..Default::default()
+            }),
+            end: Some(EventDateTime {
+                date: Some(return_day),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(get_event_local_time_range(&get_event(start, end)), Some((start, end)));
+        assert_eq!(
+            get_event_local_time_range(&vacation),
+            Some((get_start_of_local_day(first_day), get_start_of_local_day(return_day)))
+        );
+        assert_eq!(get_event_local_time_range(&Event::default()), None);
+    }
+}
+
+#[cfg(test)]
+mod test_working_location {
+    use chrono::NaiveDate;
+    use google_calendar3::api::{
+        Event, EventDateTime, EventWorkingLocationProperties, EventWorkingLocationPropertiesOfficeLocation,
+    };
+
+    use crate::{get_all_day_event_dates, get_working_location, WorkingLocation};
+
+    fn get_working_location_event(working_location_properties: EventWorkingLocationProperties) -> Event {
+        Event {
+            event_type: Some("workingLocation".to_string()),
+            working_location_properties: Some(working_location_properties),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn it_returns_working_location_of_working_location_events() {
+        let home_event = get_working_location_event(EventWorkingLocationProperties {
+            type_: Some("homeOffice".to_string()),
+            home_office: Some(serde_json::json!({})),
+            ..Default::default()
+        });
+        let office_event_without_type = get_working_location_event(EventWorkingLocationProperties {
+            office_location: Some(EventWorkingLocationPropertiesOfficeLocation {
+                label: Some("London".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        let custom_event = get_working_location_event(EventWorkingLocationProperties {
+            type_: Some("customLocation".to_string()),
+            ..Default::default()
+        });
+
+        assert_eq!(get_working_location(&home_event), Some(WorkingLocation::Home));
+        assert_eq!(
+            get_working_location(&office_event_without_type),
+            Some(WorkingLocation::Office)
+        );
+        assert_eq!(get_working_location(&custom_event), Some(WorkingLocation::Custom));
+    }
+
+    #[test]
+    fn it_returns_no_working_location_for_other_events() {
+        let meeting = Event {
+            event_type: Some("default".to_string()),
+            working_location_properties: Some(EventWorkingLocationProperties {
+                type_: Some("homeOffice".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(get_working_location(&meeting), None);
+        assert_eq!(
+            get_working_location(&get_working_location_event(EventWorkingLocationProperties::default())),
+            None
+        );
+    }
+
+    #[test]
+    fn it_returns_dates_of_all_day_events_only() {
+        let start = NaiveDate::from_ymd_opt(2025, 1, 6).unwrap();
+        let end = NaiveDate::from_ymd_opt(2025, 1, 7).unwrap();
+        let all_day_event = Event {
+            start: Some(EventDateTime {
+                date: Some(start),
+                ..Default::default()
+            }),
+            end: Some(EventDateTime {
+                date: Some(end),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(get_all_day_event_dates(&all_day_event), Some((start, end)));
+        assert_eq!(get_all_day_event_dates(&Event::default()), None);
+    }
+}
+
+#[cfg(test)]
+mod test_get_conference {
+    use google_calendar3::api::{ConferenceData, EntryPoint, Event};
+
+    use crate::{get_conference, Conference, ConferenceProvider};
+
+    #[test]
+    fn it_returns_conference_from_video_entry_point_of_conference_data() {
+        let event = Event {
+            conference_data: Some(ConferenceData {
+                entry_points: Some(vec![
+                    EntryPoint {
+                        entry_point_type: Some("phone".to_string()),
+                        uri: Some("tel:+1-555-0100".to_string()),
+                        ..Default::default()
+                    },
+                    EntryPoint {
+                        entry_point_type: Some("video".to_string()),
+                        uri: Some("https://meet.google.com/aaa-bbbb-ccc".to_string()),
+                        ..Default::default()
+                    },
+                ]),
+                ..Default::default()
+            }),
+            location: Some("https://example.zoom.us/j/123456789".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            get_conference(&event),
+            Some(Conference {
+                provider: ConferenceProvider::Meet,
+                join_url: "https://meet.google.com/aaa-bbbb-ccc".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn it_returns_first_join_link_from_location_or_description() {
+        let zoom_event = Event {
+            location: Some("Room 1, https://us02web.zoom.us/j/123456789?pwd=abc".to_string()),
+            ..Default::default()
+        };
+        let teams_event = Event {
+            description: Some(
+                "<p>Join: <a href=\"https://teams.microsoft.com/l/meetup-join/19%3ameeting_abc%40thread.v2/0\">link</a> \
+                 or https://meet.google.com/aaa-bbbb-ccc</p>"
+                    .to_string(),
+            ),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            get_conference(&zoom_event),
+            Some(Conference {
+                provider: ConferenceProvider::Zoom,
+                join_url: "https://us02web.zoom.us/j/123456789?pwd=abc".to_string(),
+            })
+        );
+        assert_eq!(
+            get_conference(&teams_event),
+            Some(Conference {
+                provider: ConferenceProvider::Teams,
+                join_url: "https://teams.microsoft.com/l/meetup-join/19%3ameeting_abc%40thread.v2/0".to_string(),
+            })
+        );
+        assert_eq!(
+            get_conference(&Event {
+                location: Some("Room 1".to_string()),
+                ..Default::default()
+            }),
+            None
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use std::{
+    fs::{File, OpenOptions, create_dir_all},
+    io::{BufRead, BufReader, ErrorKind, Write},
+    os::unix::fs::OpenOptionsExt,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::LoggerError;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditStatus {
+    pub status_text: String,
+    pub status_emoji: String,
+    pub status_expiration: i64,
+}
+
+// One line of the audit log, written for every Slack status change.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub timestamp: DateTime<Utc>,
+    pub user: String,
+    pub previous_status: Option<AuditStatus>,
+    pub new_status: AuditStatus,
+    pub event_id: Option<String>,
+    pub rule: Option<String>,
+}
+
+// Append-only JSON Lines file, kept separately from the application log so it is not rolled or filtered by log levels.
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    pub fn new(path: impl Into<PathBuf>) -> AuditLog {
+        AuditLog { path: path.into() }
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, audit_record: &AuditRecord) -> Result<(), LoggerError> {
+        let write_error = |err: String| LoggerError::WriteAuditLogError(self.path.display().to_string(), err);
+        if let Some(audit_log_dir) = self.path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            create_dir_all(audit_log_dir).map_err(|err| write_error(err.to_string()))?;
+        }
+        let mut line = serde_json::to_string(audit_record).map_err(|err| write_error(err.to_string()))?;
+        line.push('\n');
+        // Records may contain status texts of the user, so only the owner can read them.
+        let mut audit_log_file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .mode(0o600)
+            .open(&self.path)
+            .map_err(|err| write_error(err.to_string()))?;
+        // The whole line is written at once, so records of concurrent writers are not interleaved.
+        audit_log_file
+            .write_all(line.as_bytes())
+            .map_err(|err| write_error(err.to_string()))
+    }
+
+    // Missing audit log has no records yet. Invalid records, e.g. a line cut off by a crash, are skipped with a warning
+    // naming the line, so one broken line does not hide the rest of the history.
+    pub fn read(
+        &self,
+        user: Option<&str>,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<(Vec<AuditRecord>, Vec<String>), LoggerError> {
+        let read_error = |err: String| LoggerError::ReadAuditLogError(self.path.display().to_string(), err);
+        let audit_log_file = match File::open(&self.path) {
+            Ok(audit_log_file) => audit_log_file,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok((vec![], vec![])),
+            Err(err) => return Err(read_error(err.to_string())),
+        };
+        let mut audit_records = vec![];
+        let mut warnings = vec![];
+        for (index, line) in BufReader::new(audit_log_file).lines().enumerate() {
+            let line = line.map_err(|err| read_error(err.to_string()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let audit_record: AuditRecord = match serde_json::from_str(&line) {
+                Ok(audit_record) => audit_record,
+                Err(err) => {
+                    warnings.push(format!(
+                        "Skipped invalid record at line {} of audit log '{}': {}",
+                        index + 1,
+                        self.path.display(),
+                        err
+                    ));
+                    continue;
+                }
+            };
+            if user.is_some_and(|user| audit_record.user != user)
+                || since.is_some_and(|since| audit_record.timestamp < since)
+            {
+                continue;
+            }
+            audit_records.push(audit_record);
+        }
+        Ok((audit_records, warnings))
+    }
+}
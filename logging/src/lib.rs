@@ -1,10 +1,17 @@
+mod audit;
+mod log_level_controller;
+mod redaction;
+mod span;
 mod tests;
 
-use std::{fs::remove_dir_all, path::Path};
+use std::fs::read_to_string;
 
+pub use audit::{AuditLog, AuditRecord, AuditStatus};
 use log::LevelFilter;
+pub use log_level_controller::{LogLevelController, LogLevelOverride};
+pub use log4rs::Handle;
 use log4rs::{
-    Config, Handle,
+    Config,
     append::{
         console::ConsoleAppender,
         rolling_file::{
@@ -15,8 +22,16 @@ use log4rs::{
             },
         },
     },
-    config::{Appender, Root, load_config_file},
-    encode::pattern::PatternEncoder,
+    config::{Appender, Logger, RawConfig, Root},
+    encode::{Encode, json::JsonEncoder, pattern::PatternEncoder},
+};
+pub use redaction::{
+    JSON_ENCODER_KIND, PATTERN_ENCODER_KIND, REDACTED_SECRET_VALUE, RedactingEncoder, get_redacting_deserializers,
+    redact_secrets,
+};
+pub use span::{
+    CYCLE_ID_MDC_KEY, SPAN_ID_MDC_KEY, SPAN_NAME_MDC_KEY, Span, SpanRecord, enable_span_export,
+    get_otlp_traces_request, take_finished_spans,
 };
 use thiserror::Error;
 
@@ -25,22 +40,58 @@ pub const DEFAULT_LOG_FILE_PATH: &str = "logs";
 pub const DEFAULT_LOG_FILE_BASE_NAME: &str = "slack-gcal-sync";
 pub const DEFAULT_LOG_FILE_EXTENSION: &str = "log";
 pub const DEFAULT_CONSOLE_LOG_PATTERN: &str = "{d(%+)(utc)} [{f}:{L}] {h({l})} {M}:{m}{n}";
+pub const COMPACT_CONSOLE_LOG_PATTERN: &str = "{d(%H:%M:%S)} {h({l:<5})} {m}{n}";
 pub const DEFAULT_ROLLING_FILE_BASE_INDEX: u32 = 0;
 pub const DEFAULT_ROLLING_FILE_ARCHIVE_COUNT: u32 = 5;
 pub const DEFAULT_LOG_FILE_SIZE: u64 = 10 * 1024 * 1024; // 10 MB size limit
 pub const DEFAULT_CONSOLE_LOGGER_NAME: &str = "stdout";
 pub const DEFAULT_ROLLING_FILE_LOGGER_NAME: &str = "rollingfile";
+// Appenders of these kinds use a pattern encoder when no encoder is configured.
+pub const ENCODING_APPENDER_KINDS: [&str; 3] = ["console", "file", "rolling_file"];
 
 #[derive(Debug, Error)]
 pub enum LoggerError {
     #[error("Cannot read logger configuration at path: {0}. {1}")]
     ReadLoggerConfigurationError(String, String),
+
+    #[error("Cannot create logger configuration. {0}")]
+    CreateLoggerConfigurationError(String),
+
+    #[error("Cannot initialise logger. {0}")]
+    InitLoggerError(String),
+
+    #[error(
+        "Invalid log level directive: '{0}'. Use '<level>' or '<module>=<level>' with one of levels: off, error, warn, info, debug, trace."
+    )]
+    InvalidLogLevelDirectiveError(String),
+
+    #[error("Log level duration is too long: {0}.")]
+    InvalidLogLevelDurationError(String),
+
+    #[error("Cannot write audit log at path: {0}. {1}")]
+    WriteAuditLogError(String, String),
+
+    #[error("Cannot read audit log at path: {0}. {1}")]
+    ReadAuditLogError(String, String),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConsoleLogFormat {
+    Pretty,
+    Json,
+    Compact,
+}
+
+#[derive(Clone)]
 pub struct LoggerConfigurator {
     pub log_file_path: Option<String>,
     pub log_file_roller_pattern: Option<String>,
     pub log_file_size: Option<u64>,
+    pub log_file_archive_count: Option<u32>,
+    // Level set here overrides the root level of the logging configuration file as well.
+    pub log_level: Option<LevelFilter>,
+    pub console_log_format: Option<ConsoleLogFormat>,
+    pub log_to_file: Option<bool>,
 }
 
 impl Default for LoggerConfigurator {
@@ -49,73 +100,203 @@ impl Default for LoggerConfigurator {
             log_file_path: Some(Self::get_default_log_file_path()),
             log_file_roller_pattern: Some(Self::get_default_log_file_roller_pattern()),
             log_file_size: Some(DEFAULT_LOG_FILE_SIZE),
+            log_file_archive_count: Some(DEFAULT_ROLLING_FILE_ARCHIVE_COUNT),
+            log_level: None,
+            console_log_format: Some(ConsoleLogFormat::Pretty),
+            log_to_file: Some(true),
         }
     }
 }
 
 impl LoggerConfigurator {
     pub fn get_default_log_file_roller_pattern() -> String {
-        format!("{DEFAULT_LOG_FILE_PATH}/{DEFAULT_LOG_FILE_BASE_NAME}-{{}}.{DEFAULT_LOG_FILE_EXTENSION}")
+        Self::get_log_file_roller_pattern(DEFAULT_LOG_FILE_PATH)
     }
 
     pub fn get_default_log_file_path() -> String {
-        format!("{DEFAULT_LOG_FILE_PATH}/{DEFAULT_LOG_FILE_BASE_NAME}.{DEFAULT_LOG_FILE_EXTENSION}")
+        Self::get_log_file_path(DEFAULT_LOG_FILE_PATH)
+    }
+
+    pub fn get_log_file_roller_pattern(log_dir: &str) -> String {
+        format!("{log_dir}/{DEFAULT_LOG_FILE_BASE_NAME}-{{}}.{DEFAULT_LOG_FILE_EXTENSION}")
+    }
+
+    pub fn get_log_file_path(log_dir: &str) -> String {
+        format!("{log_dir}/{DEFAULT_LOG_FILE_BASE_NAME}.{DEFAULT_LOG_FILE_EXTENSION}")
     }
 
-    pub fn setup_default_logger(&self) -> Handle {
-        let stdout = ConsoleAppender::builder()
-            .encoder(Box::new(PatternEncoder::new(DEFAULT_CONSOLE_LOG_PATTERN)))
-            .build();
+    // Log files are kept in the given directory, named the same as in the default directory.
+    pub fn with_log_dir(mut self, log_dir: &str) -> Self {
+        self.log_file_path = Some(Self::get_log_file_path(log_dir));
+        self.log_file_roller_pattern = Some(Self::get_log_file_roller_pattern(log_dir));
+        self
+    }
+
+    // Building the rolling file appender creates the log file, so the default configuration is built only when it is
+    // going to be used.
+    pub fn get_default_logging_config(&self) -> Result<Config, LoggerError> {
+        self.get_logging_config(None)
+    }
+
+    fn get_console_encoder(&self) -> Box<dyn Encode> {
+        let encoder: Box<dyn Encode> = match self.console_log_format.unwrap_or(ConsoleLogFormat::Pretty) {
+            ConsoleLogFormat::Pretty => Box::new(PatternEncoder::new(DEFAULT_CONSOLE_LOG_PATTERN)),
+            ConsoleLogFormat::Json => Box::new(JsonEncoder::new()),
+            ConsoleLogFormat::Compact => Box::new(PatternEncoder::new(COMPACT_CONSOLE_LOG_PATTERN)),
+        };
+        Box::new(RedactingEncoder::new(encoder))
+    }
+
+    fn get_default_appenders(&self) -> Result<Vec<Appender>, LoggerError> {
+        let stdout = ConsoleAppender::builder().encoder(self.get_console_encoder()).build();
+        let mut appenders = vec![Appender::builder().build(DEFAULT_CONSOLE_LOGGER_NAME, Box::new(stdout))];
+        if !self.log_to_file.unwrap_or(true) {
+            return Ok(appenders);
+        }
+
+        let create_error = |err: &dyn std::fmt::Display| LoggerError::CreateLoggerConfigurationError(err.to_string());
+        let log_file_path = self.log_file_path.clone().unwrap_or(Self::get_default_log_file_path());
+        let log_file_roller_pattern = self
+            .log_file_roller_pattern
+            .clone()
+            .unwrap_or(Self::get_default_log_file_roller_pattern());
         let roller = FixedWindowRoller::builder()
             .base(DEFAULT_ROLLING_FILE_BASE_INDEX)
             .build(
-                self.log_file_roller_pattern.clone().unwrap().as_str(),
-                DEFAULT_ROLLING_FILE_ARCHIVE_COUNT,
+                log_file_roller_pattern.as_str(),
+                self.log_file_archive_count
+                    .unwrap_or(DEFAULT_ROLLING_FILE_ARCHIVE_COUNT),
             )
-            .unwrap();
-        let trigger = SizeTrigger::new(self.log_file_size.unwrap());
+            .map_err(|err| create_error(&err))?;
+        let trigger = SizeTrigger::new(self.log_file_size.unwrap_or(DEFAULT_LOG_FILE_SIZE));
         let policy = compound::CompoundPolicy::new(Box::new(trigger), Box::new(roller));
         let rollingfile = RollingFileAppender::builder()
-            .encoder(Box::new(log4rs::encode::json::JsonEncoder::new()))
-            .build(self.log_file_path.clone().unwrap().as_str(), Box::new(policy))
-            .unwrap();
-        let config = Config::builder()
-            .appender(Appender::builder().build(DEFAULT_CONSOLE_LOGGER_NAME, Box::new(stdout)))
-            .appender(Appender::builder().build(DEFAULT_ROLLING_FILE_LOGGER_NAME, Box::new(rollingfile)))
-            .build(
-                Root::builder()
-                    .appender(DEFAULT_CONSOLE_LOGGER_NAME)
-                    .appender(DEFAULT_ROLLING_FILE_LOGGER_NAME)
-                    .build(DEFAULT_LOG_LEVEL),
-            )
-            .unwrap();
+            .encoder(Box::new(RedactingEncoder::new(Box::new(JsonEncoder::new()))))
+            .build(log_file_path.as_str(), Box::new(policy))
+            .map_err(|err| create_error(&err))?;
+        appenders.push(Appender::builder().build(DEFAULT_ROLLING_FILE_LOGGER_NAME, Box::new(rollingfile)));
+        Ok(appenders)
+    }
 
-        log4rs::init_config(config).unwrap()
+    fn get_default_root(appenders: &[Appender]) -> Root {
+        Root::builder()
+            .appenders(appenders.iter().map(|appender| appender.name().to_string()))
+            .build(DEFAULT_LOG_LEVEL)
     }
 
-    pub fn apply_logging_config_from_file(
-        &mut self,
+    // Overridden modules keep appenders and additivity of loggers already configured for them.
+    fn build_logging_config(
+        appenders: Vec<Appender>,
+        mut root: Root,
+        loggers: Vec<Logger>,
+        log_level_overrides: &[LogLevelOverride],
+    ) -> Result<Config, LoggerError> {
+        let mut loggers = loggers;
+        for log_level_override in log_level_overrides {
+            let Some(module) = &log_level_override.module else {
+                root.set_level(log_level_override.level);
+                continue;
+            };
+            let logger = match loggers.iter().position(|logger| logger.name() == module) {
+                Some(index) => {
+                    let logger = loggers.remove(index);
+                    Logger::builder()
+                        .appenders(logger.appenders().to_vec())
+                        .additive(logger.additive())
+                        .build(module, log_level_override.level)
+                }
+                None => Logger::builder().build(module, log_level_override.level),
+            };
+            loggers.push(logger);
+        }
+        Config::builder()
+            .appenders(appenders)
+            .loggers(loggers)
+            .build(root)
+            .map_err(|err| LoggerError::CreateLoggerConfigurationError(err.to_string()))
+    }
+
+    // Appenders which are not valid make the whole configuration invalid, instead of being skipped as by log4rs.
+    pub fn read_logging_config_from_file(logging_config_path: &str) -> Result<Config, LoggerError> {
+        Self::read_logging_config_from_file_with_level_overrides(logging_config_path, &[])
+    }
+
+    fn read_logging_config_from_file_with_level_overrides(
         logging_config_path: &str,
-        logging_handle: &Handle,
-    ) -> Result<(), LoggerError> {
-        match load_config_file(logging_config_path, Default::default()) {
-            Ok(config_from_file) => {
-                // When setting up logger from file, we no longer rely on values set buy default setup.
-                let log_file_path = self.log_file_path.clone().unwrap();
-                let log_file_folder = Path::new(&log_file_path).parent().unwrap();
-                if log_file_folder.exists() {
-                    remove_dir_all(log_file_folder).unwrap();
+        log_level_overrides: &[LogLevelOverride],
+    ) -> Result<Config, LoggerError> {
+        let read_error = |err: &dyn std::fmt::Display| {
+            LoggerError::ReadLoggerConfigurationError(logging_config_path.to_string(), err.to_string())
+        };
+        let raw_config =
+            Self::read_raw_logging_config_from_file(logging_config_path).map_err(|err| read_error(&err))?;
+        let (appenders, appender_errors) = raw_config.appenders_lossy(&get_redacting_deserializers());
+        if !appender_errors.is_empty() {
+            return Err(read_error(&appender_errors));
+        }
+        Self::build_logging_config(appenders, raw_config.root(), raw_config.loggers(), log_level_overrides)
+            .map_err(|err| read_error(&err))
+    }
+
+    // Only YAML logging configuration files are supported, the same as when the configuration is read by log4rs.
+    // Appenders which would use the default encoder get it set explicitly, so their records are redacted too.
+    fn read_raw_logging_config_from_file(logging_config_path: &str) -> Result<RawConfig, Box<dyn std::error::Error>> {
+        let mut logging_config: serde_yaml::Value = serde_yaml::from_str(&read_to_string(logging_config_path)?)?;
+        if let Some(appenders) = logging_config
+            .get_mut("appenders")
+            .and_then(|appenders| appenders.as_mapping_mut())
+        {
+            for (_, appender) in appenders.iter_mut() {
+                let Some(appender) = appender.as_mapping_mut() else {
+                    continue;
+                };
+                let uses_default_encoder = appender
+                    .get("kind")
+                    .and_then(|kind| kind.as_str())
+                    .is_some_and(|kind| ENCODING_APPENDER_KINDS.contains(&kind))
+                    && !appender.contains_key("encoder");
+                if uses_default_encoder {
+                    let mut encoder = serde_yaml::Mapping::new();
+                    encoder.insert("kind".into(), PATTERN_ENCODER_KIND.into());
+                    appender.insert("encoder".into(), encoder.into());
                 }
-                self.log_file_path = None;
-                self.log_file_roller_pattern = None;
-                self.log_file_size = None;
-                logging_handle.set_config(config_from_file);
-                Ok(())
             }
-            Err(err) => Err(LoggerError::ReadLoggerConfigurationError(
-                logging_config_path.to_string(),
-                err.to_string(),
-            )),
         }
+        Ok(serde_yaml::from_value(logging_config)?)
+    }
+
+    // Configuration from file is used as is, default appenders are not created at all in that case.
+    pub fn get_logging_config(&self, logging_config_path: Option<&str>) -> Result<Config, LoggerError> {
+        self.get_logging_config_with_level_overrides(logging_config_path, &[])
+    }
+
+    // Configuration is built again from its source, as appenders of an active configuration cannot be reused.
+    pub fn get_logging_config_with_level_overrides(
+        &self,
+        logging_config_path: Option<&str>,
+        log_level_overrides: &[LogLevelOverride],
+    ) -> Result<Config, LoggerError> {
+        let log_level_overrides: Vec<LogLevelOverride> = self
+            .log_level
+            .map(|level| LogLevelOverride { module: None, level })
+            .into_iter()
+            .chain(log_level_overrides.iter().cloned())
+            .collect();
+        match logging_config_path {
+            Some(logging_config_path) => {
+                Self::read_logging_config_from_file_with_level_overrides(logging_config_path, &log_level_overrides)
+            }
+            None => {
+                let appenders = self.get_default_appenders()?;
+                let root = Self::get_default_root(&appenders);
+                Self::build_logging_config(appenders, root, vec![], &log_level_overrides)
+            }
+        }
+    }
+
+    // Logger can be initialised only once per process, use the returned handle to change its configuration later.
+    pub fn setup_logger(&self, logging_config_path: Option<&str>) -> Result<Handle, LoggerError> {
+        let config = self.get_logging_config(logging_config_path)?;
+        log4rs::init_config(config).map_err(|err| LoggerError::InitLoggerError(err.to_string()))
     }
 }
@@ -0,0 +1,125 @@
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use log::LevelFilter;
+use log4rs::Handle;
+
+use crate::{LoggerConfigurator, LoggerError};
+
+// Directive is either `<level>` for the root logger or `<module>=<level>`, e.g. `slack_integration=debug`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogLevelOverride {
+    pub module: Option<String>,
+    pub level: LevelFilter,
+}
+
+impl LogLevelOverride {
+    pub fn parse(directive: &str) -> Result<LogLevelOverride, LoggerError> {
+        let invalid_directive_error = || LoggerError::InvalidLogLevelDirectiveError(directive.to_string());
+        let (module, level) = match directive.trim().split_once('=') {
+            Some((module, level)) => (Some(module.trim()), level.trim()),
+            None => (None, directive.trim()),
+        };
+        if module.is_some_and(|module| module.is_empty() || module.contains(char::is_whitespace)) {
+            return Err(invalid_directive_error());
+        }
+        Ok(LogLevelOverride {
+            module: module.map(str::to_string),
+            level: LevelFilter::from_str(level).map_err(|_| invalid_directive_error())?,
+        })
+    }
+}
+
+impl Display for LogLevelOverride {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.module {
+            Some(module) => write!(f, "{}={}", module, self.level.as_str().to_lowercase()),
+            None => write!(f, "{}", self.level.as_str().to_lowercase()),
+        }
+    }
+}
+
+// Changes log levels of the running logger for a limited time. Each change rebuilds the logging configuration from its
+// source with all active overrides, so reverting an override restores the configured level.
+pub struct LogLevelController {
+    handle: Handle,
+    logger_configurator: LoggerConfigurator,
+    logging_config_path: Option<String>,
+    log_level_overrides: Vec<(LogLevelOverride, Instant)>,
+}
+
+impl LogLevelController {
+    pub fn new(
+        handle: Handle,
+        logger_configurator: LoggerConfigurator,
+        logging_config_path: Option<String>,
+    ) -> LogLevelController {
+        LogLevelController {
+            handle,
+            logger_configurator,
+            logging_config_path,
+            log_level_overrides: vec![],
+        }
+    }
+
+    // Replaces an active override of the same module.
+    pub fn set_log_level(
+        &mut self,
+        log_level_override: LogLevelOverride,
+        duration: Duration,
+    ) -> Result<(), LoggerError> {
+        let expires_at = Instant::now()
+            .checked_add(duration)
+            .ok_or_else(|| LoggerError::InvalidLogLevelDurationError(format!("{:?}", duration)))?;
+        let mut log_level_overrides = self.log_level_overrides.clone();
+        log_level_overrides.retain(|(active_override, _)| active_override.module != log_level_override.module);
+        log_level_overrides.push((log_level_override, expires_at));
+        self.apply(log_level_overrides)
+    }
+
+    pub fn reset(&mut self) -> Result<(), LoggerError> {
+        self.apply(vec![])
+    }
+
+    // Returns overrides which expired and were reverted.
+    pub fn revert_expired_log_levels(&mut self, now: Instant) -> Result<Vec<LogLevelOverride>, LoggerError> {
+        let (expired_overrides, log_level_overrides): (Vec<_>, Vec<_>) = self
+            .log_level_overrides
+            .iter()
+            .cloned()
+            .partition(|(_, expires_at)| *expires_at <= now);
+        if expired_overrides.is_empty() {
+            return Ok(vec![]);
+        }
+        self.apply(log_level_overrides)?;
+        Ok(expired_overrides
+            .into_iter()
+            .map(|(expired_override, _)| expired_override)
+            .collect())
+    }
+
+    pub fn get_next_expiration(&self) -> Option<Instant> {
+        self.log_level_overrides.iter().map(|(_, expires_at)| *expires_at).min()
+    }
+
+    pub fn get_log_level_overrides(&self) -> &[(LogLevelOverride, Instant)] {
+        &self.log_level_overrides
+    }
+
+    // Active overrides are kept unchanged when the new configuration cannot be built.
+    fn apply(&mut self, log_level_overrides: Vec<(LogLevelOverride, Instant)>) -> Result<(), LoggerError> {
+        let overrides: Vec<LogLevelOverride> = log_level_overrides
+            .iter()
+            .map(|(log_level_override, _)| log_level_override.clone())
+            .collect();
+        let config = self
+            .logger_configurator
+            .get_logging_config_with_level_overrides(self.logging_config_path.as_deref(), &overrides)?;
+        self.handle.set_config(config);
+        self.log_level_overrides = log_level_overrides;
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+use std::{borrow::Cow, sync::LazyLock};
+
+use log::Record;
+use log4rs::{
+    config::{Deserialize, Deserializers},
+    encode::{Encode, Write, json::JsonEncoderDeserializer, pattern::PatternEncoderDeserializer},
+};
+use regex::{Captures, Regex};
+
+pub const REDACTED_SECRET_VALUE: &str = "********";
+pub const PATTERN_ENCODER_KIND: &str = "pattern";
+pub const JSON_ENCODER_KIND: &str = "json";
+
+// Slack tokens (bot, user, app, refresh, ...) keep their prefix, e.g. `xoxp-`, so the kind of token is still visible.
+static SLACK_TOKEN_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b(xox[abeprs]-)[A-Za-z0-9-]+").unwrap());
+// Blocks cut off before their end line are redacted to the end of the message.
+static PEM_BLOCK_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)-----BEGIN ([A-Z0-9 ]+)-----.*?(-----END [A-Z0-9 ]+-----|$)").unwrap());
+
+pub fn redact_secrets(text: &str) -> Cow<'_, str> {
+    let text = SLACK_TOKEN_REGEX.replace_all(text, |captures: &Captures| {
+        format!("{}{}", &captures[1], REDACTED_SECRET_VALUE)
+    });
+    if !PEM_BLOCK_REGEX.is_match(&text) {
+        return text;
+    }
+    let redacted_text = PEM_BLOCK_REGEX.replace_all(&text, |captures: &Captures| {
+        format!("-----BEGIN {}-----{}", &captures[1], REDACTED_SECRET_VALUE)
+    });
+    Cow::Owned(redacted_text.into_owned())
+}
+
+// Redacts the message of a record before it is passed to the wrapped encoder, so formatting and styles of the wrapped
+// encoder are kept.
+#[derive(Debug)]
+pub struct RedactingEncoder {
+    encoder: Box<dyn Encode>,
+}
+
+impl RedactingEncoder {
+    pub fn new(encoder: Box<dyn Encode>) -> RedactingEncoder {
+        RedactingEncoder { encoder }
+    }
+}
+
+impl Encode for RedactingEncoder {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+        let message = record.args().to_string();
+        match redact_secrets(&message) {
+            Cow::Borrowed(_) => self.encoder.encode(w, record),
+            Cow::Owned(redacted_message) => self.encoder.encode(
+                w,
+                &Record::builder()
+                    .args(format_args!("{}", redacted_message))
+                    .level(record.level())
+                    .target(record.target())
+                    .module_path(record.module_path())
+                    .file(record.file())
+                    .line(record.line())
+                    .key_values(record.key_values())
+                    .build(),
+            ),
+        }
+    }
+}
+
+pub struct RedactingEncoderDeserializer<D> {
+    deserializer: D,
+}
+
+impl<D> Deserialize for RedactingEncoderDeserializer<D>
+where
+    D: Deserialize<Trait = dyn Encode>,
+{
+    type Trait = dyn Encode;
+
+    type Config = D::Config;
+
+    fn deserialize(&self, config: Self::Config, deserializers: &Deserializers) -> anyhow::Result<Box<dyn Encode>> {
+        let encoder = self.deserializer.deserialize(config, deserializers)?;
+        Ok(Box::new(RedactingEncoder::new(encoder)))
+    }
+}
+
+// Encoders read from logging configuration files redact secrets as well.
+pub fn get_redacting_deserializers() -> Deserializers {
+    let mut deserializers = Deserializers::default();
+    deserializers.insert(
+        PATTERN_ENCODER_KIND,
+        RedactingEncoderDeserializer {
+            deserializer: PatternEncoderDeserializer,
+        },
+    );
+    deserializers.insert(
+        JSON_ENCODER_KIND,
+        RedactingEncoderDeserializer {
+            deserializer: JsonEncoderDeserializer,
+        },
+    );
+    deserializers
+}
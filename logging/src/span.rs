@@ -0,0 +1,205 @@
+use std::{
+    fmt::Display,
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use log::debug;
+use serde_json::{Value, json};
+
+pub const CYCLE_ID_MDC_KEY: &str = "cycle_id";
+pub const SPAN_ID_MDC_KEY: &str = "span_id";
+pub const SPAN_NAME_MDC_KEY: &str = "span";
+pub const OTLP_SERVICE_NAME: &str = "slack-gcal-sync";
+// Status codes of OTLP spans.
+const OTLP_STATUS_CODE_OK: u8 = 1;
+const OTLP_STATUS_CODE_ERROR: u8 = 2;
+const OTLP_SPAN_KIND_INTERNAL: u8 = 1;
+
+static SPAN_EXPORT_ENABLED: AtomicBool = AtomicBool::new(false);
+static FINISHED_SPANS: Mutex<Vec<SpanRecord>> = Mutex::new(vec![]);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpanRecord {
+    pub trace_id: String,
+    pub span_id: String,
+    pub parent_span_id: Option<String>,
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub start_time: SystemTime,
+    pub end_time: SystemTime,
+    pub is_error: bool,
+}
+
+// Span ids and attributes are kept in the logging MDC while the span is open, so every log message written in the span
+// carries them, e.g. in the `mdc` field of the json encoder. MDC is thread local, spans must be finished on the thread
+// which started them, which holds for code awaited in one task on the main thread of the runtime.
+pub struct Span {
+    trace_id: String,
+    span_id: String,
+    parent_span_id: Option<String>,
+    name: String,
+    attributes: Vec<(String, String)>,
+    start_time: SystemTime,
+    started_at: Instant,
+    is_error: bool,
+    previous_mdc_values: Vec<(String, Option<String>)>,
+}
+
+impl Span {
+    // Starts a new trace, its id is the sync cycle id.
+    pub fn start_cycle(name: &str) -> Span {
+        Span::new(name, format!("{:032x}", rand::random::<u128>()), None)
+    }
+
+    // Starts a child of the open span, or a new cycle when no span is open.
+    pub fn start(name: &str) -> Span {
+        let trace_id = log_mdc::get(CYCLE_ID_MDC_KEY, |trace_id| trace_id.map(str::to_string));
+        let parent_span_id = log_mdc::get(SPAN_ID_MDC_KEY, |span_id| span_id.map(str::to_string));
+        match trace_id {
+            Some(trace_id) => Span::new(name, trace_id, parent_span_id),
+            None => Span::start_cycle(name),
+        }
+    }
+
+    fn new(name: &str, trace_id: String, parent_span_id: Option<String>) -> Span {
+        let mut span = Span {
+            trace_id: trace_id.clone(),
+            span_id: format!("{:016x}", rand::random::<u64>()),
+            parent_span_id,
+            name: name.to_string(),
+            attributes: vec![],
+            start_time: SystemTime::now(),
+            started_at: Instant::now(),
+            is_error: false,
+            previous_mdc_values: vec![],
+        };
+        span.insert_mdc_value(CYCLE_ID_MDC_KEY, trace_id);
+        span.insert_mdc_value(SPAN_ID_MDC_KEY, span.span_id.clone());
+        span.insert_mdc_value(SPAN_NAME_MDC_KEY, name.to_string());
+        span
+    }
+
+    pub fn get_trace_id(&self) -> &str {
+        &self.trace_id
+    }
+
+    pub fn set_attribute(&mut self, key: &str, value: impl Display) {
+        let value = value.to_string();
+        self.insert_mdc_value(key, value.clone());
+        match self
+            .attributes
+            .iter_mut()
+            .find(|(attribute_key, _)| attribute_key == key)
+        {
+            Some((_, attribute_value)) => *attribute_value = value,
+            None => self.attributes.push((key.to_string(), value)),
+        }
+    }
+
+    pub fn set_error(&mut self, error: impl Display) {
+        self.is_error = true;
+        self.set_attribute("error", error);
+    }
+
+    // Values replaced by the span are restored when it finishes.
+    fn insert_mdc_value(&mut self, key: &str, value: String) {
+        let previous_value = log_mdc::insert(key, value);
+        if !self
+            .previous_mdc_values
+            .iter()
+            .any(|(previous_key, _)| previous_key == key)
+        {
+            self.previous_mdc_values.push((key.to_string(), previous_value));
+        }
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        debug!(
+            "Span '{}' finished in {} ms.",
+            self.name,
+            self.started_at.elapsed().as_millis()
+        );
+        for (key, previous_value) in self.previous_mdc_values.drain(..).rev() {
+            match previous_value {
+                Some(previous_value) => log_mdc::insert(key, previous_value),
+                None => log_mdc::remove(&key),
+            };
+        }
+        if SPAN_EXPORT_ENABLED.load(Ordering::Relaxed) {
+            let span_record = SpanRecord {
+                trace_id: self.trace_id.clone(),
+                span_id: self.span_id.clone(),
+                parent_span_id: self.parent_span_id.clone(),
+                name: self.name.clone(),
+                attributes: self.attributes.clone(),
+                start_time: self.start_time,
+                end_time: self.start_time + self.started_at.elapsed(),
+                is_error: self.is_error,
+            };
+            FINISHED_SPANS
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .push(span_record);
+        }
+    }
+}
+
+// Finished spans are kept for export only when enabled, otherwise they are dropped.
+pub fn enable_span_export() {
+    SPAN_EXPORT_ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn take_finished_spans() -> Vec<SpanRecord> {
+    std::mem::take(&mut *FINISHED_SPANS.lock().unwrap_or_else(|err| err.into_inner()))
+}
+
+fn get_unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
+
+// Request body of OTLP/HTTP traces endpoint in JSON encoding, see
+// https://opentelemetry.io/docs/specs/otlp/#json-protobuf-encoding.
+pub fn get_otlp_traces_request(span_records: &[SpanRecord]) -> Value {
+    let spans: Vec<Value> = span_records
+        .iter()
+        .map(|span_record| {
+            json!({
+                "traceId": span_record.trace_id,
+                "spanId": span_record.span_id,
+                "parentSpanId": span_record.parent_span_id.clone().unwrap_or_default(),
+                "name": span_record.name,
+                "kind": OTLP_SPAN_KIND_INTERNAL,
+                "startTimeUnixNano": get_unix_nanos(span_record.start_time),
+                "endTimeUnixNano": get_unix_nanos(span_record.end_time),
+                "attributes": span_record
+                    .attributes
+                    .iter()
+                    .map(|(key, value)| json!({"key": key, "value": {"stringValue": value}}))
+                    .collect::<Vec<Value>>(),
+                "status": {
+                    "code": if span_record.is_error { OTLP_STATUS_CODE_ERROR } else { OTLP_STATUS_CODE_OK }
+                },
+            })
+        })
+        .collect();
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [{"key": "service.name", "value": {"stringValue": OTLP_SERVICE_NAME}}]
+            },
+            "scopeSpans": [{
+                "scope": {"name": OTLP_SERVICE_NAME},
+                "spans": spans,
+            }],
+        }]
+    })
+}
@@ -1,11 +1,22 @@
 mod tests;
 
-use reqwest::{Client, Response, Result as ReqwestResult};
+use std::{collections::BTreeMap, time::Duration};
+
+use metrics::counter;
+use reqwest::{header::RETRY_AFTER, Client, RequestBuilder, Response, StatusCode};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use tokio::time::sleep;
 
 pub const INVALID_AUTH: &str = "invalid_auth";
 pub const UNKNOWN_METHOD: &str = "unknown_method";
+pub const SLACK_OAUTH_SCOPES_HEADER: &str = "x-oauth-scopes";
+pub const REQUIRED_SLACK_USER_OAUTH_SCOPES: [&str; 2] = ["users.profile:read", "users.profile:write"];
+pub const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+pub const DEFAULT_RATE_LIMIT_RETRY_AFTER_SECONDS: u64 = 30;
+// Waits block the synchronization, so longer rate limits are left to the next synchronization.
+pub const DEFAULT_MAX_RATE_LIMIT_WAIT_SECONDS: u64 = 10;
+pub const RATE_LIMIT_WAITS_METRIC: &str = "slack_gcal_sync_rate_limit_waits_total";
 
 #[derive(Debug, Error)]
 pub enum SlackApiError {
@@ -17,9 +28,17 @@ pub enum SlackApiError {
     )]
     UnknownSlackApiMethodError(String),
 
+    #[error(
+        "Slack user oauth token is missing required scope(s): {0}. Add them in `OAuth & Permissions -> User Token Scopes` of your Slack app and reinstall the app in your workspace."
+    )]
+    MissingScopesError(String),
+
     #[error("Error calling Slack API: {0}")]
     SlackAPIError(String),
 
+    #[error("Slack API is rate limited, retry after {0} seconds.")]
+    RateLimitedError(u64),
+
     #[error("Failed to make request to Slack api: {0}")]
     ResponseError(#[from] reqwest::Error),
 }
@@ -27,12 +46,23 @@ pub enum SlackApiError {
 pub const SLACK_API_BASE_URL: &str = "https://slack.com/api";
 pub const SLACK_USER_PROFILE_GET_ENDPOINT: &str = "users.profile.get";
 pub const SLACK_USER_PROFILE_SET_ENDPOINT: &str = "users.profile.set";
+pub const SLACK_AUTH_TEST_ENDPOINT: &str = "auth.test";
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct ProfileData {
     pub status_text: String,
     pub status_emoji: String,
     pub status_expiration: i64,
+    // Custom profile fields by field id, e.g. `Xf0123456789`. Fields are only set, Slack returns them in a different shape.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty", skip_deserializing)]
+    pub fields: BTreeMap<String, ProfileField>,
+}
+
+// Empty value clears the custom profile field.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct ProfileField {
+    pub value: String,
+    pub alt: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -54,10 +84,40 @@ pub struct ProfileResponse {
     pub body: ProfileResponseBody,
 }
 
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct AuthTestResponseBody {
+    pub ok: bool,
+    pub url: Option<String>,
+    pub team: Option<String>,
+    pub user: Option<String>,
+    pub team_id: Option<String>,
+    pub user_id: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct AuthTestResponse {
+    pub status: reqwest::StatusCode,
+    pub url: String,
+    pub scopes: Vec<String>,
+    pub body: AuthTestResponseBody,
+}
+
+impl AuthTestResponse {
+    pub fn get_missing_scopes(&self, required_scopes: &[&str]) -> Vec<String> {
+        required_scopes
+            .iter()
+            .filter(|required_scope| !self.scopes.iter().any(|scope| scope == *required_scope))
+            .map(|required_scope| required_scope.to_string())
+            .collect()
+    }
+}
+
 pub struct SlackApiClient {
     api_base_url: String,
     auth_token: String,
     client: Client,
+    max_rate_limit_wait: Duration,
 }
 
 impl SlackApiClient {
@@ -66,14 +126,52 @@ impl SlackApiClient {
             api_base_url,
             auth_token,
             client,
+            max_rate_limit_wait: Duration::from_secs(DEFAULT_MAX_RATE_LIMIT_WAIT_SECONDS),
         }
     }
 
+    pub fn with_max_rate_limit_wait(mut self, max_rate_limit_wait: Duration) -> SlackApiClient {
+        self.max_rate_limit_wait = max_rate_limit_wait;
+        self
+    }
+
     pub fn get_endpoint_url(&self, endpoint: &str) -> String {
         format!("{}/{}", self.api_base_url, endpoint)
     }
 
-    async fn handle_response(&self, response: ReqwestResult<Response>) -> Result<ProfileResponse, SlackApiError> {
+    // Rate limited requests are sent again after the time Slack asks to wait in `Retry-After` header. Requests are given
+    // up when waiting in total would take longer than the maximum rate limit wait.
+    async fn send(&self, endpoint: &str, request: RequestBuilder) -> Result<Response, SlackApiError> {
+        let mut retries = 0;
+        let mut rate_limit_wait = Duration::ZERO;
+        loop {
+            let response = match request.try_clone() {
+                Some(request) => request.send().await?,
+                None => return Ok(request.send().await?),
+            };
+            if response.status() != StatusCode::TOO_MANY_REQUESTS || retries == MAX_RATE_LIMIT_RETRIES {
+                return Ok(response);
+            }
+            let retry_after_seconds = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|retry_after| retry_after.to_str().ok())
+                .and_then(|retry_after| retry_after.trim().parse::<u64>().ok())
+                .unwrap_or(DEFAULT_RATE_LIMIT_RETRY_AFTER_SECONDS);
+            rate_limit_wait = rate_limit_wait.saturating_add(Duration::from_secs(retry_after_seconds));
+            if rate_limit_wait > self.max_rate_limit_wait {
+                return Err(SlackApiError::RateLimitedError(retry_after_seconds));
+            }
+            counter!(RATE_LIMIT_WAITS_METRIC, "api" => "slack", "method" => endpoint.to_string()).increment(1);
+            sleep(Duration::from_secs(retry_after_seconds)).await;
+            retries += 1;
+        }
+    }
+
+    async fn handle_response(
+        &self,
+        response: Result<Response, SlackApiError>,
+    ) -> Result<ProfileResponse, SlackApiError> {
         match response {
             Ok(response) => {
                 let status = response.status();
@@ -86,24 +184,87 @@ impl SlackApiClient {
                 };
 
                 match profile_response.body.error {
-                    Some(error) => match error.as_str() {
-                        INVALID_AUTH => Err(SlackApiError::InvalidAuthError),
-                        UNKNOWN_METHOD => Err(SlackApiError::UnknownSlackApiMethodError(profile_response.url)),
-                        _ => Err(SlackApiError::SlackAPIError(error.to_string())),
-                    },
+                    Some(error) => Err(get_slack_api_error(error.as_str(), profile_response.url)),
                     None => Ok(profile_response),
                 }
             }
-            Err(error) => Err(SlackApiError::ResponseError(error)),
+            Err(error) => Err(error),
+        }
+    }
+
+    async fn handle_auth_test_response(
+        &self,
+        response: Result<Response, SlackApiError>,
+    ) -> Result<AuthTestResponse, SlackApiError> {
+        match response {
+            Ok(response) => {
+                let status = response.status();
+                let url = response.url().to_string();
+                // Slack lists scopes granted to the token as a comma separated header value.
+                let scopes = response
+                    .headers()
+                    .get(SLACK_OAUTH_SCOPES_HEADER)
+                    .and_then(|scopes| scopes.to_str().ok())
+                    .map(|scopes| {
+                        scopes
+                            .split(',')
+                            .map(|scope| scope.trim().to_string())
+                            .filter(|scope| !scope.is_empty())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let response_body: AuthTestResponseBody = response.json().await?;
+                let auth_test_response = AuthTestResponse {
+                    status,
+                    url,
+                    scopes,
+                    body: response_body,
+                };
+
+                match auth_test_response.body.error {
+                    Some(error) => Err(get_slack_api_error(error.as_str(), auth_test_response.url)),
+                    None => Ok(auth_test_response),
+                }
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    pub async fn auth_test(&self, endpoint: &str) -> Result<AuthTestResponse, SlackApiError> {
+        let response = self
+            .send(
+                endpoint,
+                self.client
+                    .post(self.get_endpoint_url(endpoint))
+                    .bearer_auth(self.auth_token.as_str()),
+            )
+            .await;
+
+        self.handle_auth_test_response(response).await
+    }
+
+    pub async fn validate_auth_token(
+        &self,
+        endpoint: &str,
+        required_scopes: &[&str],
+    ) -> Result<AuthTestResponse, SlackApiError> {
+        let auth_test_response = self.auth_test(endpoint).await?;
+        let missing_scopes = auth_test_response.get_missing_scopes(required_scopes);
+        if missing_scopes.is_empty() {
+            Ok(auth_test_response)
+        } else {
+            Err(SlackApiError::MissingScopesError(missing_scopes.join(", ")))
         }
     }
 
     pub async fn get_user_profile(&self, endpoint: &str) -> Result<ProfileResponse, SlackApiError> {
         let response = self
-            .client
-            .get(self.get_endpoint_url(endpoint))
-            .bearer_auth(self.auth_token.as_str())
-            .send()
+            .send(
+                endpoint,
+                self.client
+                    .get(self.get_endpoint_url(endpoint))
+                    .bearer_auth(self.auth_token.as_str()),
+            )
             .await;
 
         self.handle_response(response).await
@@ -115,13 +276,23 @@ impl SlackApiClient {
         profile_request_body: &ProfileRequestBody,
     ) -> Result<ProfileResponse, SlackApiError> {
         let response = self
-            .client
-            .post(self.get_endpoint_url(endpoint))
-            .bearer_auth(self.auth_token.as_str())
-            .json(&profile_request_body)
-            .send()
+            .send(
+                endpoint,
+                self.client
+                    .post(self.get_endpoint_url(endpoint))
+                    .bearer_auth(self.auth_token.as_str())
+                    .json(&profile_request_body),
+            )
             .await;
 
         self.handle_response(response).await
     }
 }
+
+fn get_slack_api_error(error: &str, url: String) -> SlackApiError {
+    match error {
+        INVALID_AUTH => SlackApiError::InvalidAuthError,
+        UNKNOWN_METHOD => SlackApiError::UnknownSlackApiMethodError(url),
+        _ => SlackApiError::SlackAPIError(error.to_string()),
+    }
+}
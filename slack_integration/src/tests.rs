@@ -1,12 +1,14 @@
 #[cfg(test)]
 mod test_slack_api_client {
+    use std::time::{Duration, Instant};
+
     use mockito::{Mock, ServerGuard};
     use reqwest::Client;
     use serde_json::Value;
 
     use crate::{
-        ProfileData, ProfileRequestBody, SlackApiClient, SlackApiError, INVALID_AUTH, SLACK_USER_PROFILE_GET_ENDPOINT,
-        SLACK_USER_PROFILE_SET_ENDPOINT, UNKNOWN_METHOD,
+        ProfileData, ProfileField, ProfileRequestBody, SlackApiClient, SlackApiError, INVALID_AUTH,
+        SLACK_USER_PROFILE_GET_ENDPOINT, SLACK_USER_PROFILE_SET_ENDPOINT, UNKNOWN_METHOD,
     };
 
     async fn get_mock_user_profile_setup(
@@ -107,6 +109,7 @@ mod test_slack_api_client {
             status_text: new_status_text.to_string(),
             status_emoji: new_status_emoji.to_string(),
             status_expiration: new_status_expiration,
+            fields: Default::default(),
         };
         let profile_request_body = ProfileRequestBody { profile: profile_data };
 
@@ -129,6 +132,35 @@ mod test_slack_api_client {
         assert_eq!(inner_profile.status_expiration, new_status_expiration);
     }
 
+    #[test]
+    fn test_serializes_custom_profile_fields_only_when_set() {
+        let profile_data = ProfileData {
+            status_text: "In a meeting".to_string(),
+            status_emoji: ":calendar:".to_string(),
+            status_expiration: 0,
+            fields: [(
+                "Xf0123456789".to_string(),
+                ProfileField {
+                    value: "https://meet.google.com/aaa-bbbb-ccc".to_string(),
+                    alt: "Join call".to_string(),
+                },
+            )]
+            .into(),
+        };
+
+        assert_eq!(
+            serde_json::to_value(&profile_data).unwrap()["fields"],
+            serde_json::json!({"Xf0123456789": {"value": "https://meet.google.com/aaa-bbbb-ccc", "alt": "Join call"}})
+        );
+        assert!(serde_json::to_value(ProfileData {
+            fields: Default::default(),
+            ..profile_data
+        })
+        .unwrap()
+        .get("fields")
+        .is_none());
+    }
+
     #[tokio::test]
     async fn test_raises_response_error() {
         let _ = env_logger::try_init();
@@ -164,7 +196,7 @@ mod test_slack_api_client {
 
         let error = slack_api_client.get_user_profile(SLACK_USER_PROFILE_GET_ENDPOINT).await;
         mock_slack_api.assert_async().await;
-        assert!(matches!(error, Err(SlackApiError::InvalidAuthError { .. })));
+        assert!(matches!(error, Err(SlackApiError::InvalidAuthError)));
         assert!(error.unwrap_err().to_string().contains("Invalid authorization token."));
     }
 
@@ -226,4 +258,215 @@ mod test_slack_api_client {
             format!("Error calling Slack API: {}", error_message)
         );
     }
+
+    #[tokio::test]
+    async fn test_retries_rate_limited_request_after_retry_after_header() {
+        let _ = env_logger::try_init();
+        let mock_auth_token = "secret_token";
+        let mock_slack_api_response = serde_json::json!({
+            "ok": true,
+            "profile": {
+              "status_text": "In a meeting",
+              "status_emoji": ":calendar:",
+              "status_expiration": 0,
+            }
+        });
+        let mut mock_slack_api_server = mockito::Server::new_async().await;
+        let mock_rate_limited_slack_api = mock_slack_api_server
+            .mock("GET", format!("/{}", SLACK_USER_PROFILE_GET_ENDPOINT).as_str())
+            .with_status(429)
+            .with_header("retry-after", "0")
+            .with_body(serde_json::json!({"ok": false, "error": "ratelimited"}).to_string())
+            .expect(2)
+            .create_async()
+            .await;
+        let mock_slack_api = mock_slack_api_server
+            .mock("GET", format!("/{}", SLACK_USER_PROFILE_GET_ENDPOINT).as_str())
+            .with_status(200)
+            .with_body(mock_slack_api_response.to_string())
+            .create_async()
+            .await;
+        let slack_api_client =
+            SlackApiClient::new(mock_slack_api_server.url(), mock_auth_token.to_string(), Client::new());
+
+        let user_profile_response = slack_api_client
+            .get_user_profile(SLACK_USER_PROFILE_GET_ENDPOINT)
+            .await
+            .unwrap();
+        mock_rate_limited_slack_api.assert_async().await;
+        mock_slack_api.assert_async().await;
+
+        assert_eq!(user_profile_response.status, reqwest::StatusCode::OK);
+        assert_eq!(user_profile_response.body.profile.unwrap().status_text, "In a meeting");
+    }
+    #[tokio::test]
+    async fn test_gives_up_rate_limited_request_when_retry_after_is_longer_than_max_wait() {
+        let _ = env_logger::try_init();
+        let mock_auth_token = "secret_token";
+        let mut mock_slack_api_server = mockito::Server::new_async().await;
+        let mock_rate_limited_slack_api = mock_slack_api_server
+            .mock("GET", format!("/{}", SLACK_USER_PROFILE_GET_ENDPOINT).as_str())
+            .with_status(429)
+            .with_header("retry-after", "86400")
+            .with_body(serde_json::json!({"ok": false, "error": "ratelimited"}).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+        let slack_api_client =
+            SlackApiClient::new(mock_slack_api_server.url(), mock_auth_token.to_string(), Client::new())
+                .with_max_rate_limit_wait(Duration::from_secs(60));
+
+        let started_at = Instant::now();
+        let error = slack_api_client
+            .get_user_profile(SLACK_USER_PROFILE_GET_ENDPOINT)
+            .await
+            .unwrap_err();
+        mock_rate_limited_slack_api.assert_async().await;
+
+        assert!(matches!(error, SlackApiError::RateLimitedError(86400)));
+        assert!(started_at.elapsed() < Duration::from_secs(5));
+    }
+}
+
+#[cfg(test)]
+mod test_slack_api_client_auth_test {
+    use mockito::{Mock, ServerGuard};
+    use reqwest::Client;
+    use serde_json::Value;
+
+    use crate::{
+        SlackApiClient, SlackApiError, INVALID_AUTH, REQUIRED_SLACK_USER_OAUTH_SCOPES, SLACK_AUTH_TEST_ENDPOINT,
+        SLACK_OAUTH_SCOPES_HEADER,
+    };
+
+    async fn get_mock_auth_test_setup(
+        mock_slack_api_response: &Value,
+        mock_auth_token: &str,
+        scopes: &str,
+    ) -> (Mock, ServerGuard) {
+        let mut mock_slack_api_server = mockito::Server::new_async().await;
+        let mock_slack_api = mock_slack_api_server
+            .mock("POST", format!("/{}", SLACK_AUTH_TEST_ENDPOINT).as_str())
+            .match_header("authorization", format!("Bearer {}", mock_auth_token).as_str())
+            .with_header(SLACK_OAUTH_SCOPES_HEADER, scopes)
+            .with_body(mock_slack_api_response.to_string())
+            .with_status(200)
+            .create_async()
+            .await;
+
+        (mock_slack_api, mock_slack_api_server)
+    }
+
+    fn get_auth_test_ok_response() -> Value {
+        serde_json::json!({
+            "ok": true,
+            "url": "https://lab.slack.com/",
+            "team": "lab",
+            "user": "tom",
+            "team_id": "T12345",
+            "user_id": "U12345",
+        })
+    }
+
+    #[tokio::test]
+    async fn test_auth_test_returns_identity_and_scopes() {
+        let _ = env_logger::try_init();
+        let mock_slack_api_response = get_auth_test_ok_response();
+        let mock_auth_token = "secret_token";
+        let (mock_slack_api, mock_slack_api_server) = get_mock_auth_test_setup(
+            &mock_slack_api_response,
+            mock_auth_token,
+            "users.profile:read, users.profile:write,dnd:write",
+        )
+        .await;
+        let slack_api_client =
+            SlackApiClient::new(mock_slack_api_server.url(), mock_auth_token.to_string(), Client::new());
+
+        let auth_test_response = slack_api_client.auth_test(SLACK_AUTH_TEST_ENDPOINT).await.unwrap();
+        mock_slack_api.assert_async().await;
+
+        assert_eq!(auth_test_response.status, reqwest::StatusCode::OK);
+        assert_eq!(
+            auth_test_response.url,
+            slack_api_client.get_endpoint_url(SLACK_AUTH_TEST_ENDPOINT)
+        );
+        assert_eq!(
+            auth_test_response.scopes,
+            vec!["users.profile:read", "users.profile:write", "dnd:write"]
+        );
+        assert!(auth_test_response.body.ok);
+        assert_eq!(auth_test_response.body.user.as_deref(), Some("tom"));
+        assert_eq!(auth_test_response.body.user_id.as_deref(), Some("U12345"));
+        assert_eq!(auth_test_response.body.team.as_deref(), Some("lab"));
+        assert_eq!(auth_test_response.body.team_id.as_deref(), Some("T12345"));
+        assert_eq!(auth_test_response.body.error, None);
+    }
+
+    #[tokio::test]
+    async fn test_validate_auth_token_passes_when_all_required_scopes_are_granted() {
+        let _ = env_logger::try_init();
+        let mock_slack_api_response = get_auth_test_ok_response();
+        let mock_auth_token = "secret_token";
+        let (mock_slack_api, mock_slack_api_server) = get_mock_auth_test_setup(
+            &mock_slack_api_response,
+            mock_auth_token,
+            "users.profile:read,users.profile:write",
+        )
+        .await;
+        let slack_api_client =
+            SlackApiClient::new(mock_slack_api_server.url(), mock_auth_token.to_string(), Client::new());
+
+        let auth_test_response = slack_api_client
+            .validate_auth_token(SLACK_AUTH_TEST_ENDPOINT, &REQUIRED_SLACK_USER_OAUTH_SCOPES)
+            .await
+            .unwrap();
+        mock_slack_api.assert_async().await;
+
+        assert!(auth_test_response
+            .get_missing_scopes(&REQUIRED_SLACK_USER_OAUTH_SCOPES)
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_validate_auth_token_raises_missing_scopes_error() {
+        let _ = env_logger::try_init();
+        let mock_slack_api_response = get_auth_test_ok_response();
+        let mock_auth_token = "secret_token";
+        let (mock_slack_api, mock_slack_api_server) =
+            get_mock_auth_test_setup(&mock_slack_api_response, mock_auth_token, "users.profile:read").await;
+        let slack_api_client =
+            SlackApiClient::new(mock_slack_api_server.url(), mock_auth_token.to_string(), Client::new());
+
+        let error = slack_api_client
+            .validate_auth_token(SLACK_AUTH_TEST_ENDPOINT, &["users.profile:write", "dnd:write"])
+            .await;
+        mock_slack_api.assert_async().await;
+
+        assert!(matches!(error, Err(SlackApiError::MissingScopesError { .. })));
+        assert!(error
+            .unwrap_err()
+            .to_string()
+            .contains("Slack user oauth token is missing required scope(s): users.profile:write, dnd:write."));
+    }
+
+    #[tokio::test]
+    async fn test_validate_auth_token_raises_invalid_auth_error() {
+        let _ = env_logger::try_init();
+        let mock_slack_api_response = serde_json::json!({
+            "ok": false,
+            "error": INVALID_AUTH,
+        });
+        let mock_auth_token = "invalid_token";
+        let (mock_slack_api, mock_slack_api_server) =
+            get_mock_auth_test_setup(&mock_slack_api_response, mock_auth_token, "").await;
+        let slack_api_client =
+            SlackApiClient::new(mock_slack_api_server.url(), mock_auth_token.to_string(), Client::new());
+
+        let error = slack_api_client
+            .validate_auth_token(SLACK_AUTH_TEST_ENDPOINT, &REQUIRED_SLACK_USER_OAUTH_SCOPES)
+            .await;
+        mock_slack_api.assert_async().await;
+
+        assert!(matches!(error, Err(SlackApiError::InvalidAuthError)));
+    }
 }
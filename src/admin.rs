@@ -0,0 +1,217 @@
+use std::{
+    fs::{remove_file, set_permissions, Permissions},
+    io::{Read, Write},
+    os::unix::{fs::PermissionsExt, net::UnixStream as StdUnixStream},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use log::{error, info, warn};
+use logging::{LogLevelController, LogLevelOverride};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+    sync::{mpsc, oneshot},
+    time::timeout,
+};
+
+pub const ADMIN_SOCKET_FILE_NAME: &str = "slack-gcal-sync.sock";
+pub const ADMIN_COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+pub const MAX_ADMIN_COMMAND_LENGTH: u64 = 1024;
+pub const ADMIN_REQUEST_QUEUE_SIZE: usize = 16;
+pub const ADMIN_RESPONSE_OK: &str = "OK";
+pub const ADMIN_RESPONSE_ERROR: &str = "ERROR";
+
+// Socket is created in the user's runtime directory, which is private to the user, and falls back to temp directory.
+pub fn get_admin_socket_path(admin_socket_path: Option<&str>) -> PathBuf {
+    match admin_socket_path {
+        Some(admin_socket_path) => PathBuf::from(admin_socket_path),
+        None => std::env::var_os("XDG_RUNTIME_DIR")
+            .map(PathBuf::from)
+            .unwrap_or(std::env::temp_dir())
+            .join(ADMIN_SOCKET_FILE_NAME),
+    }
+}
+
+// Admin commands are sent one per connection as a single line. Response starts with `OK` or `ERROR` line.
+pub struct AdminServer {
+    listener: UnixListener,
+    admin_socket_path: PathBuf,
+}
+
+impl AdminServer {
+    pub fn bind(admin_socket_path: &Path) -> Result<AdminServer> {
+        if admin_socket_path.exists() {
+            if StdUnixStream::connect(admin_socket_path).is_ok() {
+                bail!(
+                    "Admin socket '{}' is already used by another running daemon.",
+                    admin_socket_path.display()
+                );
+            }
+            // Socket file left behind by a daemon which did not stop cleanly.
+            remove_file(admin_socket_path)
+                .with_context(|| format!("Failed to remove stale admin socket '{}'.", admin_socket_path.display()))?;
+        }
+        let listener = UnixListener::bind(admin_socket_path)
+            .with_context(|| format!("Failed to listen on admin socket '{}'.", admin_socket_path.display()))?;
+        set_permissions(admin_socket_path, Permissions::from_mode(0o600)).with_context(|| {
+            format!(
+                "Failed to restrict access to admin socket '{}'.",
+                admin_socket_path.display()
+            )
+        })?;
+        Ok(AdminServer {
+            listener,
+            admin_socket_path: admin_socket_path.to_path_buf(),
+        })
+    }
+
+    pub async fn accept(&self) -> Result<UnixStream> {
+        let (stream, _) = self.listener.accept().await?;
+        Ok(stream)
+    }
+
+    pub fn get_admin_socket_path(&self) -> &Path {
+        &self.admin_socket_path
+    }
+}
+
+impl Drop for AdminServer {
+    fn drop(&mut self) {
+        if let Err(err) = remove_file(&self.admin_socket_path) {
+            warn!(
+                "Failed to remove admin socket '{}'. {}",
+                self.admin_socket_path.display(),
+                err
+            );
+        }
+    }
+}
+
+// Admin command is sent to the daemon loop, which owns the log level controller, and the response is sent back to the
+// connection it was read from.
+pub struct AdminRequest {
+    pub command: String,
+    response_sender: oneshot::Sender<String>,
+}
+
+impl AdminRequest {
+    pub fn handle(self, log_level_controller: &mut LogLevelController) {
+        let response = get_admin_response(handle_admin_command(self.command.trim(), log_level_controller));
+        // Client which disconnected in the meantime does not need the response.
+        let _ = self.response_sender.send(response);
+    }
+}
+
+fn get_admin_response(result: Result<String>) -> String {
+    match result {
+        Ok(message) => format!("{}\n{}\n", ADMIN_RESPONSE_OK, message),
+        Err(err) => format!("{}\n{}\n", ADMIN_RESPONSE_ERROR, err),
+    }
+}
+
+// Connections are handled on their own tasks, so a client which connects and sends nothing does not hold up
+// synchronization, signals or log level reverts. Only commands read in full are sent to the daemon loop.
+pub fn spawn_admin_connection_handler(stream: UnixStream, admin_request_sender: mpsc::Sender<AdminRequest>) {
+    tokio::spawn(async move {
+        if let Err(err) = handle_admin_connection(stream, admin_request_sender).await {
+            error!("Failed to handle admin command: {:?}", err);
+        }
+    });
+}
+
+async fn handle_admin_connection(stream: UnixStream, admin_request_sender: mpsc::Sender<AdminRequest>) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut command = String::new();
+    timeout(
+        ADMIN_COMMAND_TIMEOUT,
+        BufReader::new(reader.take(MAX_ADMIN_COMMAND_LENGTH)).read_line(&mut command),
+    )
+    .await
+    .with_context(|| "Timed out waiting for admin command.")??;
+    let response = if command.ends_with('\n') {
+        let (response_sender, response_receiver) = oneshot::channel();
+        admin_request_sender
+            .send(AdminRequest {
+                command,
+                response_sender,
+            })
+            .await
+            .map_err(|_| anyhow!("Daemon is stopping."))?;
+        response_receiver
+            .await
+            .with_context(|| "Daemon did not respond to admin command.")?
+    } else {
+        get_admin_response(Err(anyhow!(
+            "Admin command must be a single line of at most {} bytes.",
+            MAX_ADMIN_COMMAND_LENGTH
+        )))
+    };
+    writer.write_all(response.as_bytes()).await?;
+    Ok(())
+}
+
+fn handle_admin_command(command: &str, log_level_controller: &mut LogLevelController) -> Result<String> {
+    match command.split_whitespace().collect::<Vec<&str>>().as_slice() {
+        ["log-level", "set", directive, duration] => {
+            let log_level_override = LogLevelOverride::parse(directive)?;
+            let duration =
+                humantime::parse_duration(duration).with_context(|| format!("Invalid duration '{}'.", duration))?;
+            log_level_controller.set_log_level(log_level_override.clone(), duration)?;
+            let message = format!(
+                "Log level set to '{}' for {}.",
+                log_level_override,
+                humantime::format_duration(duration)
+            );
+            info!("{}", message);
+            Ok(message)
+        }
+        ["log-level", "reset"] => {
+            log_level_controller.reset()?;
+            info!("Log levels reset to configured ones.");
+            Ok("Log levels reset to configured ones.".to_string())
+        }
+        ["log-level", "show"] => Ok(get_log_level_overrides_description(log_level_controller)),
+        _ => bail!("Unknown admin command '{}'.", command),
+    }
+}
+
+fn get_log_level_overrides_description(log_level_controller: &LogLevelController) -> String {
+    let now = Instant::now();
+    let log_level_overrides = log_level_controller.get_log_level_overrides();
+    if log_level_overrides.is_empty() {
+        return "No log level overrides are active, configured log levels are used.".to_string();
+    }
+    log_level_overrides
+        .iter()
+        .map(|(log_level_override, expires_at)| {
+            // Rounded to whole seconds to keep the output readable.
+            let expires_in = Duration::from_secs(expires_at.saturating_duration_since(now).as_secs());
+            format!(
+                "{} (reverts in {})",
+                log_level_override,
+                humantime::format_duration(expires_in)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub fn send_admin_command(admin_socket_path: &Path, command: &str) -> Result<String> {
+    let mut stream = StdUnixStream::connect(admin_socket_path).with_context(|| {
+        format!(
+            "Failed to connect to admin socket '{}'. Is the daemon running?",
+            admin_socket_path.display()
+        )
+    })?;
+    stream.set_read_timeout(Some(ADMIN_COMMAND_TIMEOUT))?;
+    stream.write_all(format!("{}\n", command).as_bytes())?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    match response.split_once('\n') {
+        Some((ADMIN_RESPONSE_OK, message)) => Ok(message.trim_end().to_string()),
+        Some((ADMIN_RESPONSE_ERROR, message)) => bail!("Daemon rejected the command. {}", message.trim_end()),
+        _ => bail!("Unexpected response from the daemon: '{}'.", response.trim_end()),
+    }
+}
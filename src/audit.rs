@@ -0,0 +1,57 @@
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use configuration::{ApplicationConfigurationData, ApplicationConfigurationGetter};
+use logging::AuditLog;
+
+use crate::cli::{AuditArgs, AuditCommand, AuditShowArgs};
+
+// Accepts a duration before now, e.g. `2h`, an RFC 3339 timestamp or a date, which is the start of the day in UTC.
+pub fn parse_since(since: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(duration) = humantime::parse_duration(since) {
+        return Ok(Utc::now() - duration);
+    }
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(since) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(since, "%Y-%m-%d")
+        .map(|date| date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc())
+        .map_err(|_| {
+            format!(
+                "'{}' is not a duration, e.g. '2h', an RFC 3339 timestamp or a date, e.g. '2025-01-15'.",
+                since
+            )
+        })
+}
+
+fn show_audit_log(
+    audit_show_args: AuditShowArgs,
+    cli_application_configuration_data: ApplicationConfigurationData,
+) -> Result<()> {
+    // Audit log path only needs the merged values, the rest of the configuration does not have to be valid.
+    let (application_configuration_data, _) = ApplicationConfigurationGetter::new(cli_application_configuration_data)?
+        .get_application_configuration_data_with_sources();
+    let audit_log = AuditLog::new(application_configuration_data.get_audit_log_path());
+    let (audit_records, warnings) = audit_log.read(audit_show_args.user.as_deref(), audit_show_args.since)?;
+    for warning in warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    if audit_records.is_empty() {
+        eprintln!(
+            "No status changes found in audit log '{}'.",
+            audit_log.get_path().display()
+        );
+    }
+    for audit_record in audit_records {
+        println!("{}", serde_json::to_string(&audit_record)?);
+    }
+    Ok(())
+}
+
+pub fn run_audit(
+    audit_args: AuditArgs,
+    cli_application_configuration_data: ApplicationConfigurationData,
+) -> Result<()> {
+    match audit_args.command {
+        AuditCommand::Show(audit_show_args) => show_audit_log(audit_show_args, cli_application_configuration_data),
+    }
+}
@@ -0,0 +1,286 @@
+use std::{net::SocketAddr, time::Duration};
+
+use chrono::{DateTime, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use configuration::{ApplicationConfigurationData, ConfigurationFormat, SLACK_USER_OAUTH_TOKEN_ENV_VAR};
+
+use crate::{audit::parse_since, pause::parse_until};
+
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(flatten)]
+    pub application_configuration_data: ApplicationConfigurationData,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    #[command(about = "Check the application setup and report problems with suggested fixes.")]
+    Doctor,
+
+    #[command(about = "Create application configuration and secret files by answering a few questions.")]
+    Init(InitArgs),
+
+    #[command(about = "Inspect the application configuration.")]
+    Config(ConfigArgs),
+
+    #[command(
+        about = "Keep synchronizing periodically. Configuration is reloaded when its files change or on SIGHUP."
+    )]
+    Daemon(DaemonArgs),
+
+    #[command(about = "Change log levels of the running daemon for a limited time.")]
+    LogLevel(LogLevelArgs),
+
+    #[command(about = "Query the audit log of Slack status changes.")]
+    Audit(AuditArgs),
+
+    #[command(
+        about = "Stop changing the Slack status, e.g. to keep a status set manually. Pauses until resumed if no end is given."
+    )]
+    Pause(PauseArgs),
+
+    #[command(about = "Start changing the Slack status again.")]
+    Resume(ResumeArgs),
+
+    #[command(about = "Print Slack status transitions planned for today from calendar events and status rules.")]
+    Timeline,
+}
+
+pub const ADMIN_SOCKET_PATH_ENV_VAR: &str = "SLACK_GCAL_SYNC_ADMIN_SOCKET_PATH";
+pub const ADMIN_SOCKET_PATH_HELP: &str =
+    "Path of the daemon admin socket. Defaults to slack-gcal-sync.sock in $XDG_RUNTIME_DIR or temp directory.";
+
+#[derive(Args, Debug)]
+pub struct DaemonArgs {
+    #[arg(
+        long,
+        default_value_t = 60,
+        env = "SLACK_GCAL_SYNC_SYNC_INTERVAL_SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Number of seconds between synchronizations."
+    )]
+    pub sync_interval_seconds: u64,
+
+    #[arg(
+        long,
+        default_value_t = 5,
+        env = "SLACK_GCAL_SYNC_CONFIG_WATCH_INTERVAL_SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Number of seconds between checks for changes of configuration files."
+    )]
+    pub config_watch_interval_seconds: u64,
+
+    #[arg(long, env = ADMIN_SOCKET_PATH_ENV_VAR, help = ADMIN_SOCKET_PATH_HELP)]
+    pub admin_socket_path: Option<String>,
+
+    #[arg(
+        long,
+        env = "SLACK_GCAL_SYNC_METRICS_ADDRESS",
+        help = "Address to serve Prometheus metrics on at /metrics, e.g. 0.0.0.0:9090. Metrics are not served if not given."
+    )]
+    pub metrics_address: Option<SocketAddr>,
+
+    #[arg(
+        long,
+        env = "SLACK_GCAL_SYNC_HEALTH_ADDRESS",
+        help = "Address to serve /healthz and /readyz probes on, e.g. 0.0.0.0:8080. Can be the same as metrics address. Probes are not served if not given."
+    )]
+    pub health_address: Option<SocketAddr>,
+
+    #[arg(
+        long,
+        env = "SLACK_GCAL_SYNC_OTLP_ENDPOINT",
+        help = "OTLP/HTTP endpoint of an OpenTelemetry collector to export spans of sync cycles to, e.g. http://localhost:4318. Spans are not exported if not given."
+    )]
+    pub otlp_endpoint: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct LogLevelArgs {
+    #[arg(long, env = ADMIN_SOCKET_PATH_ENV_VAR, help = ADMIN_SOCKET_PATH_HELP)]
+    pub admin_socket_path: Option<String>,
+
+    #[command(subcommand)]
+    pub command: LogLevelCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum LogLevelCommand {
+    #[command(about = "Set log level of the root logger or a module until the given time passes.")]
+    Set(LogLevelSetArgs),
+
+    #[command(about = "Revert all log level changes to the configured levels.")]
+    Reset,
+
+    #[command(about = "Show active log level changes and when they revert.")]
+    Show,
+}
+
+#[derive(Args, Debug)]
+pub struct LogLevelSetArgs {
+    #[arg(help = "Log level as '<level>' or '<module>=<level>', e.g. 'slack_integration=debug'.")]
+    pub directive: String,
+
+    #[arg(
+        long = "for",
+        default_value = "15m",
+        value_parser = humantime::parse_duration,
+        help = "How long the log level is kept before it reverts, e.g. '30s', '15m' or '1h 30m'."
+    )]
+    pub duration: Duration,
+}
+
+#[derive(Args, Debug)]
+pub struct PauseArgs {
+    #[arg(
+        long = "for",
+        value_parser = humantime::parse_duration,
+        conflicts_with = "until",
+        help = "How long to pause for, e.g. '2h' or '1h 30m'."
+    )]
+    pub duration: Option<Duration>,
+
+    #[arg(
+        long,
+        value_parser = parse_until,
+        help = "When to resume, as local time today, e.g. '17:30', a date, e.g. '2025-01-20' (start of the day in local time) or an RFC 3339 timestamp."
+    )]
+    pub until: Option<DateTime<Utc>>,
+}
+
+#[derive(Args, Debug)]
+pub struct ResumeArgs {
+    #[arg(
+        long,
+        help = "Replace the Slack status set manually on the next status update, otherwise it is kept until it is cleared or expires."
+    )]
+    pub replace_status: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct AuditArgs {
+    #[command(subcommand)]
+    pub command: AuditCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AuditCommand {
+    #[command(about = "Print recorded status changes as JSON lines, oldest first.")]
+    Show(AuditShowArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct AuditShowArgs {
+    #[arg(long, help = "Only show status changes of this Slack user.")]
+    pub user: Option<String>,
+
+    #[arg(
+        long,
+        value_parser = parse_since,
+        help = "Only show status changes since a duration ago, e.g. '2h', an RFC 3339 timestamp or a date, e.g. '2025-01-15' (UTC)."
+    )]
+    pub since: Option<DateTime<Utc>>,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    #[command(about = "Print the effective application configuration with secrets masked.")]
+    Show(ConfigShowArgs),
+
+    #[command(about = "Print JSON Schema of the application configuration file for editor autocompletion.")]
+    Schema,
+
+    #[command(about = "Manage named configuration profiles.")]
+    Profiles(ConfigProfilesArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigProfilesArgs {
+    #[command(subcommand)]
+    pub command: ConfigProfilesCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigProfilesCommand {
+    #[command(about = "List profiles defined in application config files and the values they override.")]
+    List,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigShowArgs {
+    #[arg(
+        long,
+        help = "Print where each value comes from: default, configuration file, command line argument or environment variable."
+    )]
+    pub explain: bool,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum SlackUserOauthTokenSource {
+    File,
+    Env,
+}
+
+#[derive(Args, Debug)]
+pub struct InitArgs {
+    #[arg(
+        long,
+        help = "Do not prompt for answers. Values are taken from command line arguments or defaults."
+    )]
+    pub non_interactive: bool,
+
+    #[arg(long, help = "Overwrite files which already exist.")]
+    pub force: bool,
+
+    #[arg(
+        long,
+        help = "The id of the calendar to synchronize. Usually your gmail email account."
+    )]
+    pub calendar_id: Option<String>,
+
+    #[arg(
+        long,
+        help = "Path to the service account key json file downloaded from Google Cloud Project. It is copied to service account key path."
+    )]
+    pub downloaded_service_account_key_path: Option<String>,
+
+    #[arg(long, help = "Path where the service account key json file is stored.")]
+    pub service_account_key_path: Option<String>,
+
+    #[arg(long, value_enum, help = "Where the slack user oauth token is read from.")]
+    pub slack_user_oauth_token_source: Option<SlackUserOauthTokenSource>,
+
+    #[arg(
+        long,
+        env = SLACK_USER_OAUTH_TOKEN_ENV_VAR,
+        hide_env_values = true,
+        help = "Value of slack user oauth token, used when token source is file. Command line arguments are visible to other users of the system, prefer the environment variable or the prompt."
+    )]
+    pub slack_user_oauth_token: Option<String>,
+
+    #[arg(long, help = "Path where the slack user oauth token json file is stored.")]
+    pub slack_user_oauth_token_path: Option<String>,
+
+    #[arg(long, help = "Path to the log4rs logging configuration file.")]
+    pub logging_config_path: Option<String>,
+
+    #[arg(long, help = "Path where the application configuration file is stored.")]
+    pub application_config_path: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Format of the application configuration file. Defaults to the format of the file extension, json for unknown extensions."
+    )]
+    pub application_config_format: Option<ConfigurationFormat>,
+}
@@ -0,0 +1,97 @@
+use anyhow::{bail, Result};
+use configuration::{
+    get_application_configuration_json_schema, ApplicationConfigurationData, ApplicationConfigurationGetter,
+    ConfigurationSources,
+};
+
+use crate::cli::{ConfigArgs, ConfigCommand, ConfigProfilesCommand, ConfigShowArgs};
+
+const NOT_SET_VALUE: &str = "<not set>";
+
+fn show_configuration(
+    config_show_args: ConfigShowArgs,
+    cli_application_configuration_data: ApplicationConfigurationData,
+    cli_configuration_sources: ConfigurationSources,
+) -> Result<()> {
+    let application_configuration_getter = ApplicationConfigurationGetter::new(cli_application_configuration_data)?
+        .with_cli_configuration_sources(cli_configuration_sources);
+    let (application_configuration_data, configuration_sources) =
+        application_configuration_getter.get_application_configuration_data_with_sources();
+
+    println!("Effective application configuration:");
+    for (field, value) in application_configuration_data.get_masked_field_values() {
+        let value = value.unwrap_or(NOT_SET_VALUE.to_string());
+        match (config_show_args.explain, configuration_sources.get(field)) {
+            (true, Some(configuration_source)) => println!("  {}: {} (from {})", field, value, configuration_source),
+            _ => println!("  {}: {}", field, value),
+        }
+    }
+
+    if let Err(err) = application_configuration_getter.get_merged_application_configuration_data() {
+        bail!(err)
+    }
+    Ok(())
+}
+
+fn list_profiles(
+    cli_application_configuration_data: ApplicationConfigurationData,
+    cli_configuration_sources: ConfigurationSources,
+) -> Result<()> {
+    let application_configuration_getter = ApplicationConfigurationGetter::new(cli_application_configuration_data)?
+        .with_cli_configuration_sources(cli_configuration_sources);
+    let profiles = application_configuration_getter.get_profiles();
+    let (application_configuration_data, configuration_sources) =
+        application_configuration_getter.get_application_configuration_data_with_sources();
+
+    if profiles.is_empty() {
+        println!("No profiles defined. Add them to `profiles` in an application config file.");
+        return Ok(());
+    }
+    println!("Available profiles:");
+    for (name, (profile, configuration_source)) in profiles {
+        let overridden_fields = match profile.get_set_field_names() {
+            field_names if field_names.is_empty() => "nothing".to_string(),
+            field_names => field_names.join(", "),
+        };
+        match application_configuration_data.profile.as_ref() == Some(&name) {
+            true => println!(
+                "* {} (selected from {}): overrides {} (from {})",
+                name,
+                configuration_sources
+                    .get("profile")
+                    .map(|profile_source| profile_source.to_string())
+                    .unwrap_or_default(),
+                overridden_fields,
+                configuration_source
+            ),
+            false => println!(
+                "  {}: overrides {} (from {})",
+                name, overridden_fields, configuration_source
+            ),
+        }
+    }
+    Ok(())
+}
+
+fn show_schema() -> Result<()> {
+    println!("{:#}", get_application_configuration_json_schema());
+    Ok(())
+}
+
+pub fn run_config(
+    config_args: ConfigArgs,
+    cli_application_configuration_data: ApplicationConfigurationData,
+    cli_configuration_sources: ConfigurationSources,
+) -> Result<()> {
+    match config_args.command {
+        ConfigCommand::Show(config_show_args) => show_configuration(
+            config_show_args,
+            cli_application_configuration_data,
+            cli_configuration_sources,
+        ),
+        ConfigCommand::Schema => show_schema(),
+        ConfigCommand::Profiles(config_profiles_args) => match config_profiles_args.command {
+            ConfigProfilesCommand::List => list_profiles(cli_application_configuration_data, cli_configuration_sources),
+        },
+    }
+}
@@ -0,0 +1,213 @@
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, Utc};
+use configuration::{ApplicationConfigurationData, ApplicationConfigurationReloader, ConfigurationSources};
+use log::{error, info, warn};
+use tokio::{
+    signal::unix::{signal, SignalKind},
+    sync::mpsc,
+    time::{interval, sleep, sleep_until},
+};
+
+use crate::{
+    admin::{get_admin_socket_path, spawn_admin_connection_handler, AdminServer, ADMIN_REQUEST_QUEUE_SIZE},
+    cli::DaemonArgs,
+    health::HealthState,
+    http_server::{start_http_server, HttpRoutes},
+    otlp::OtlpExporter,
+    setup_logging,
+    status::{get_local_time_zone_description, TIME_ZONE_ENV_VAR},
+    sync,
+    sync_metrics::install_metrics_recorder,
+};
+
+// Wall clock instant is converted to a duration every time, so a changed system clock is followed.
+async fn sleep_until_status_transition(next_status_transition: Option<DateTime<Utc>>) {
+    match next_status_transition {
+        Some(next_status_transition) => sleep((next_status_transition - Utc::now()).to_std().unwrap_or_default()).await,
+        None => std::future::pending().await,
+    }
+}
+
+async fn sleep_until_log_level_reverts(next_expiration: Option<Instant>) {
+    match next_expiration {
+        Some(next_expiration) => sleep_until(next_expiration.into()).await,
+        None => std::future::pending().await,
+    }
+}
+
+// Metrics and health endpoints configured with the same address are served by one server.
+async fn start_http_servers(daemon_args: &DaemonArgs, health_state: &HealthState) -> Result<()> {
+    let mut http_servers: Vec<(SocketAddr, HttpRoutes)> = vec![];
+    if let Some(metrics_address) = daemon_args.metrics_address {
+        let http_routes = HttpRoutes {
+            prometheus_handle: Some(install_metrics_recorder()?),
+            health_state: None,
+        };
+        http_servers.push((metrics_address, http_routes));
+    }
+    if let Some(health_address) = daemon_args.health_address {
+        match http_servers.iter_mut().find(|(address, _)| *address == health_address) {
+            Some((_, http_routes)) => http_routes.health_state = Some(health_state.clone()),
+            None => http_servers.push((
+                health_address,
+                HttpRoutes {
+                    prometheus_handle: None,
+                    health_state: Some(health_state.clone()),
+                },
+            )),
+        }
+    }
+    for (address, http_routes) in http_servers {
+        start_http_server(address, http_routes).await?;
+    }
+    Ok(())
+}
+
+// Timer of the next status transition is replaced after every synchronization, as events may have changed. It is kept
+// when synchronization fails, so a transition planned before is still applied on time.
+async fn run_sync(
+    application_configuration_reloader: &ApplicationConfigurationReloader,
+    health_state: &HealthState,
+    otlp_exporter: Option<&OtlpExporter>,
+    next_status_transition: &mut Option<DateTime<Utc>>,
+) {
+    let application_configuration = application_configuration_reloader.get_application_configuration();
+    match sync(&application_configuration, Some(health_state)).await {
+        Ok(status_transition) => {
+            *next_status_transition = status_transition;
+            match status_transition {
+                Some(status_transition) => {
+                    info!("Next status transition planned at {}.", status_transition.to_rfc3339())
+                }
+                None => info!("No more status transitions planned for today."),
+            }
+        }
+        Err(err) => error!("Synchronization failed: {:?}", err),
+    }
+    if let Some(otlp_exporter) = otlp_exporter {
+        otlp_exporter.export_finished_spans();
+    }
+}
+
+pub async fn run_daemon(
+    daemon_args: DaemonArgs,
+    cli_application_configuration_data: ApplicationConfigurationData,
+    cli_configuration_sources: ConfigurationSources,
+) -> Result<()> {
+    let mut log_level_controller = setup_logging(&cli_application_configuration_data)?;
+
+    info!("Loading application configuration.");
+    let mut application_configuration_reloader =
+        ApplicationConfigurationReloader::new(cli_application_configuration_data, cli_configuration_sources)
+            .with_context(|| "Failed to configure application.")?;
+    info!("Successfully loaded application configuration.");
+    let logging_config_path = application_configuration_reloader
+        .get_application_configuration()
+        .logging_config_path
+        .clone();
+
+    let mut sync_interval = interval(Duration::from_secs(daemon_args.sync_interval_seconds));
+    let mut config_watch_interval = interval(Duration::from_secs(daemon_args.config_watch_interval_seconds));
+    let mut hangup_signal = signal(SignalKind::hangup()).with_context(|| "Failed to listen for SIGHUP signal.")?;
+    let admin_server = AdminServer::bind(&get_admin_socket_path(daemon_args.admin_socket_path.as_deref()))?;
+    let (admin_request_sender, mut admin_request_receiver) = mpsc::channel(ADMIN_REQUEST_QUEUE_SIZE);
+    let health_state = HealthState::new(Duration::from_secs(daemon_args.sync_interval_seconds));
+    health_state.set_configuration_loaded(true);
+    health_state.record_scheduler_heartbeat();
+    start_http_servers(&daemon_args, &health_state).await?;
+    let otlp_exporter = daemon_args.otlp_endpoint.as_deref().map(OtlpExporter::new);
+    info!(
+        "Running as a daemon, synchronizing every {} seconds. Send SIGHUP to reload the configuration.",
+        daemon_args.sync_interval_seconds
+    );
+    info!(
+        "Listening for admin commands on: '{}'.",
+        admin_server.get_admin_socket_path().display()
+    );
+    info!(
+        "Using local time zone {} for today's events and working hours without a time zone.",
+        get_local_time_zone_description(std::env::var(TIME_ZONE_ENV_VAR).ok().as_deref(), *Local::now().offset())
+    );
+
+    let mut next_status_transition: Option<DateTime<Utc>> = None;
+    loop {
+        health_state.record_scheduler_heartbeat();
+        let reload_result = tokio::select! {
+            _ = sync_interval.tick() => {
+                run_sync(
+                    &application_configuration_reloader,
+                    &health_state,
+                    otlp_exporter.as_ref(),
+                    &mut next_status_transition,
+                )
+                .await;
+                continue;
+            }
+            _ = sleep_until_status_transition(next_status_transition) => {
+                info!("Applying status transition planned at {}.", next_status_transition.unwrap_or_default().to_rfc3339());
+                // Cleared first, so a failed synchronization does not fire the same timer again immediately.
+                next_status_transition = None;
+                run_sync(
+                    &application_configuration_reloader,
+                    &health_state,
+                    otlp_exporter.as_ref(),
+                    &mut next_status_transition,
+                )
+                .await;
+                continue;
+            }
+            connection = admin_server.accept() => {
+                match connection {
+                    Ok(stream) => spawn_admin_connection_handler(stream, admin_request_sender.clone()),
+                    Err(err) => error!("Failed to accept admin connection: {:?}", err),
+                }
+                continue;
+            }
+            Some(admin_request) = admin_request_receiver.recv() => {
+                admin_request.handle(&mut log_level_controller);
+                continue;
+            }
+            _ = sleep_until_log_level_reverts(log_level_controller.get_next_expiration()) => {
+                match log_level_controller.revert_expired_log_levels(Instant::now()) {
+                    Ok(expired_overrides) => {
+                        for expired_override in expired_overrides {
+                            info!("Log level '{}' expired and was reverted.", expired_override);
+                        }
+                    }
+                    Err(err) => error!("Failed to revert expired log levels: {}", err),
+                }
+                continue;
+            }
+            _ = config_watch_interval.tick() => application_configuration_reloader.reload_if_changed(),
+            _ = hangup_signal.recv() => {
+                info!("Received SIGHUP. Reloading application configuration.");
+                Some(application_configuration_reloader.reload())
+            }
+            _ = tokio::signal::ctrl_c() => {
+                info!("Received interrupt. Stopping the daemon.");
+                return Ok(());
+            }
+        };
+        match reload_result {
+            Some(Ok(())) => {
+                let application_configuration = application_configuration_reloader.get_application_configuration();
+                if application_configuration.logging_config_path != logging_config_path {
+                    warn!(
+                        "Logging configuration path changed to: '{}'. It will be used after restart.",
+                        application_configuration.logging_config_path
+                    );
+                }
+            }
+            Some(Err(err)) => error!(
+                "Failed to reload application configuration, keeping the previous one. {}",
+                err
+            ),
+            None => {}
+        }
+    }
+}
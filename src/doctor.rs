@@ -0,0 +1,702 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use chrono::{FixedOffset, Local};
+use configuration::{
+    find_application_config_file, get_user_config_dir, read_configuration, ApplicationConfigurationData,
+    ApplicationConfigurationGetter, ConfigurationError, ConfigurationFormat, ConfigurationSource, Secret, SecretSource,
+    SyncState, WorkingHours, APPLICATION_CONFIG_FILE_EXTENSIONS, APPLICATION_CONFIG_FILE_STEM,
+    DEFAULT_APPLICATION_CONFIG_PATH, DEFAULT_LOGGING_CONFIG_PATH, SERVICE_ACCOUNT_KEY_ENV_VAR,
+    SLACK_USER_OAUTH_TOKEN_ENV_VAR, SYSTEM_CONFIG_DIR,
+};
+use gcal_integration::{
+    get_calendar_access_role, get_calendar_hub, get_service_account_authenticator, GoogleCalendarIntegrationError,
+};
+use google_calendar3::yup_oauth2::ServiceAccountKey;
+use logging::LoggerConfigurator;
+use reqwest::Client;
+use slack_integration::{
+    SlackApiClient, SlackApiError, REQUIRED_SLACK_USER_OAUTH_SCOPES, SLACK_API_BASE_URL, SLACK_AUTH_TEST_ENDPOINT,
+};
+
+use crate::{
+    pause::REPLACE_MANUAL_STATUS_HINT,
+    status::{get_local_time_zone_description, TIME_ZONE_ENV_VAR},
+};
+
+const GOOGLE_CLOUD_DOCS_HINT: &str = "See `Setting Up Integration With Google Cloud API` in README.";
+const SLACK_DOCS_HINT: &str = "See `Setting Up Integration With Slack API` in README.";
+const CALENDAR_READ_ACCESS_ROLES: [&str; 3] = ["reader", "writer", "owner"];
+const MERGED_CONFIGURATION_CHECK_NAME: &str = "Merged application configuration";
+const APPLICATION_CONFIG_FILE_HINT: &str =
+    "Fix the file at the reported line and column, it must match the example in `Application config file` section of README.";
+
+pub enum DoctorCheckOutcome {
+    Pass(String),
+    Warn(String, String),
+    Fail(String, String),
+    Skip(String),
+}
+
+pub struct DoctorCheck {
+    pub name: String,
+    pub outcome: DoctorCheckOutcome,
+}
+
+impl DoctorCheck {
+    fn new(name: &str, outcome: DoctorCheckOutcome) -> Self {
+        DoctorCheck {
+            name: name.to_string(),
+            outcome,
+        }
+    }
+
+    pub fn print(&self) {
+        match &self.outcome {
+            DoctorCheckOutcome::Pass(details) => println!("[PASS] {}: {}", self.name, details),
+            DoctorCheckOutcome::Warn(details, hint) => {
+                println!("[WARN] {}: {}", self.name, details);
+                println!("       Hint: {}", hint);
+            }
+            DoctorCheckOutcome::Fail(details, hint) => {
+                println!("[FAIL] {}: {}", self.name, details);
+                println!("       Hint: {}", hint);
+            }
+            DoctorCheckOutcome::Skip(reason) => println!("[SKIP] {}: {}", self.name, reason),
+        }
+    }
+}
+
+fn read_application_config_file(
+    name: &str,
+    application_config_path: &str,
+    application_config_format: Option<ConfigurationFormat>,
+) -> DoctorCheck {
+    let application_config_format =
+        application_config_format.unwrap_or(ConfigurationFormat::from_path(application_config_path));
+    let outcome =
+        match read_configuration::<ApplicationConfigurationData>(application_config_path, application_config_format) {
+            Ok(_) => DoctorCheckOutcome::Pass(format!(
+                "loaded from path: '{}' as {:?}.",
+                application_config_path, application_config_format
+            )),
+            Err(err) => DoctorCheckOutcome::Fail(err.to_string(), APPLICATION_CONFIG_FILE_HINT.to_string()),
+        };
+    DoctorCheck::new(name, outcome)
+}
+
+fn check_application_config_file(
+    application_config_path: &str,
+    application_config_format: Option<ConfigurationFormat>,
+) -> DoctorCheck {
+    let name = "Project configuration file";
+    if !Path::new(application_config_path).exists() {
+        return DoctorCheck::new(
+            name,
+            DoctorCheckOutcome::Warn(
+                format!(
+                    "no file found at path: '{}'. Built-in defaults, system and user config files, environment variables and command line arguments are used.",
+                    application_config_path
+                ),
+                "Create the file as shown in `Application config file` section of README or pass all values via command line arguments.".to_string(),
+            ),
+        );
+    }
+    read_application_config_file(name, application_config_path, application_config_format)
+}
+
+// System and user config files are optional, the first file found in the directory is used.
+fn check_config_dir_file(name: &str, config_dir: Option<&Path>) -> DoctorCheck {
+    let Some(config_dir) = config_dir else {
+        return skip(
+            name,
+            "config directory is not known, neither `XDG_CONFIG_HOME` nor `HOME` is set.",
+        );
+    };
+    match find_application_config_file(config_dir) {
+        Some(application_config_path) => read_application_config_file(name, &application_config_path, None),
+        None => skip(
+            name,
+            &format!(
+                "no file found at path: '{}/{}.{{{}}}'.",
+                config_dir.display(),
+                APPLICATION_CONFIG_FILE_STEM,
+                APPLICATION_CONFIG_FILE_EXTENSIONS.join(",")
+            ),
+        ),
+    }
+}
+
+fn check_unknown_fields(application_configuration_getter: &ApplicationConfigurationGetter) -> Option<DoctorCheck> {
+    let unknown_fields: Vec<String> = application_configuration_getter
+        .get_configuration_layers()
+        .iter()
+        .filter(|configuration_layer| !configuration_layer.unknown_fields.is_empty())
+        .map(|configuration_layer| {
+            format!(
+                "{} in {}",
+                configuration_layer.unknown_fields.join(", "),
+                configuration_layer.source
+            )
+        })
+        .collect();
+    if unknown_fields.is_empty() {
+        return None;
+    }
+    Some(DoctorCheck::new(
+        "Unknown configuration fields",
+        DoctorCheckOutcome::Warn(
+            format!("ignored unknown field(s): {}.", unknown_fields.join("; ")),
+            "Check the field names for typos. Generate JSON Schema with `slack-gcal-sync config schema` to get autocompletion in your editor."
+                .to_string(),
+        ),
+    ))
+}
+
+fn check_command_line_arguments(cli_application_configuration_data: &ApplicationConfigurationData) -> DoctorCheck {
+    let provided_values: Vec<&str> = [
+        ("calendar_id", cli_application_configuration_data.calendar_id.is_some()),
+        (
+            "service_account_key_path",
+            cli_application_configuration_data.service_account_key_path.is_some(),
+        ),
+        (
+            "service_account_key",
+            cli_application_configuration_data.service_account_key.is_some(),
+        ),
+        (
+            "service_account_key_secret",
+            cli_application_configuration_data.service_account_key_secret.is_some(),
+        ),
+        (
+            "slack_user_oauth_token_path",
+            cli_application_configuration_data.slack_user_oauth_token_path.is_some(),
+        ),
+        (
+            "slack_user_oauth_token",
+            cli_application_configuration_data.slack_user_oauth_token.is_some(),
+        ),
+        (
+            "slack_user_oauth_token_secret",
+            cli_application_configuration_data
+                .slack_user_oauth_token_secret
+                .is_some(),
+        ),
+        (
+            "logging_config_path",
+            cli_application_configuration_data.logging_config_path.is_some(),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(field, is_provided)| is_provided.then_some(field))
+    .collect();
+
+    let details = if provided_values.is_empty() {
+        "no values provided, nothing to override.".to_string()
+    } else {
+        format!("overriding values: {}.", provided_values.join(", "))
+    };
+    DoctorCheck::new(
+        "Command line arguments and environment variables",
+        DoctorCheckOutcome::Pass(details),
+    )
+}
+
+fn check_service_account_key(
+    application_configuration_getter: &ApplicationConfigurationGetter,
+    application_configuration_data: &ApplicationConfigurationData,
+) -> (DoctorCheck, Option<ServiceAccountKey>) {
+    let name = "Service account key";
+    let (service_account_key_source, service_account_key) = match (
+        application_configuration_data.service_account_key.as_ref(),
+        application_configuration_data.service_account_key_secret.as_deref(),
+    ) {
+        (Some(service_account_key_json), _) => (
+            "service account key value".to_string(),
+            application_configuration_getter
+                .get_service_account_key_from_json(service_account_key_json.expose_secret()),
+        ),
+        (None, Some(service_account_key_secret)) => (
+            format!(
+                "secret source '{}'",
+                SecretSource::parse(service_account_key_secret).scheme
+            ),
+            application_configuration_getter.get_service_account_key_from_secret(service_account_key_secret),
+        ),
+        (None, None) => {
+            let service_account_key_path = application_configuration_data
+                .service_account_key_path
+                .as_deref()
+                .unwrap_or_default();
+            (
+                format!("path: '{}'", service_account_key_path),
+                application_configuration_getter.get_service_account_key(service_account_key_path),
+            )
+        }
+    };
+    let service_account_key = match service_account_key {
+        Ok(service_account_key) => service_account_key,
+        Err(err) => {
+            return (
+                DoctorCheck::new(
+                    name,
+                    DoctorCheckOutcome::Fail(
+                        err.to_string(),
+                        format!(
+                            "Download a json key of your service account from Google Cloud Console and save it at the configured path or set its contents in `{}` environment variable. {}",
+                            SERVICE_ACCOUNT_KEY_ENV_VAR, GOOGLE_CLOUD_DOCS_HINT
+                        ),
+                    ),
+                ),
+                None,
+            )
+        }
+    };
+
+    let mut problems = Vec::new();
+    if service_account_key.key_type.as_deref() != Some("service_account") {
+        problems.push("`type` must be `service_account`");
+    }
+    if !service_account_key.private_key.contains("PRIVATE KEY") {
+        problems.push("`private_key` is not a PEM encoded private key");
+    }
+    if service_account_key.client_email.is_empty() {
+        problems.push("`client_email` is empty");
+    }
+
+    if problems.is_empty() {
+        (
+            DoctorCheck::new(
+                name,
+                DoctorCheckOutcome::Pass(format!(
+                    "valid key for service account: '{}'.",
+                    service_account_key.client_email
+                )),
+            ),
+            Some(service_account_key),
+        )
+    } else {
+        (
+            DoctorCheck::new(
+                name,
+                DoctorCheckOutcome::Fail(
+                    format!(
+                        "invalid key at {}: {}.",
+                        service_account_key_source,
+                        problems.join(", ")
+                    ),
+                    format!(
+                        "Use the unmodified json key file downloaded for your service account. {}",
+                        GOOGLE_CLOUD_DOCS_HINT
+                    ),
+                ),
+            ),
+            None,
+        )
+    }
+}
+
+async fn get_access_role(
+    service_account_key: ServiceAccountKey,
+    calendar_id: &str,
+) -> Result<String, GoogleCalendarIntegrationError> {
+    let service_account_authenticator = get_service_account_authenticator(service_account_key).await?;
+    let hub = get_calendar_hub(service_account_authenticator)?;
+    get_calendar_access_role(&hub, calendar_id).await
+}
+
+async fn check_calendar_access(service_account_key: ServiceAccountKey, calendar_id: &str) -> DoctorCheck {
+    let name = "Google Calendar access";
+    let client_email = service_account_key.client_email.clone();
+    let share_calendar_hint = format!(
+        "In Google Calendar settings share the calendar with '{}' using `See all event details` permission. {}",
+        client_email, GOOGLE_CLOUD_DOCS_HINT
+    );
+
+    let access_role = get_access_role(service_account_key, calendar_id).await;
+
+    let outcome = match access_role {
+        Ok(access_role) if CALENDAR_READ_ACCESS_ROLES.contains(&access_role.as_str()) => DoctorCheckOutcome::Pass(
+            format!("service account has '{}' access to calendar: '{}'.", access_role, calendar_id),
+        ),
+        Ok(access_role) => DoctorCheckOutcome::Fail(
+            format!(
+                "service account has only '{}' access to calendar: '{}', event details are not visible.",
+                access_role, calendar_id
+            ),
+            share_calendar_hint,
+        ),
+        Err(err @ GoogleCalendarIntegrationError::CalendarNotFoundError(_)) => DoctorCheckOutcome::Fail(
+            err.to_string(),
+            format!(
+                "Check `calendar_id` matches `Calendar ID` in `Integrate calendar` section of calendar settings. {}",
+                share_calendar_hint
+            ),
+        ),
+        Err(err @ GoogleCalendarIntegrationError::CalendarAccessForbiddenError(..)) => DoctorCheckOutcome::Fail(
+            err.to_string(),
+            format!(
+                "Enable Google Calendar API in your Google Cloud project in `APIs & Services -> Library`. {}",
+                GOOGLE_CLOUD_DOCS_HINT
+            ),
+        ),
+        Err(err) => DoctorCheckOutcome::Fail(
+            format!("{:#}", anyhow::Error::new(err)),
+            "Check the service account key was not deleted in Google Cloud Console and that you are connected to the internet."
+                .to_string(),
+        ),
+    };
+    DoctorCheck::new(name, outcome)
+}
+
+fn check_slack_user_oauth_token_source(
+    application_configuration_getter: &ApplicationConfigurationGetter,
+    application_configuration_data: &ApplicationConfigurationData,
+) -> (DoctorCheck, Option<Secret<String>>) {
+    let name = "Slack user oauth token source";
+    if let Some(slack_user_oauth_token) = application_configuration_data.slack_user_oauth_token.as_ref() {
+        return (
+            DoctorCheck::new(
+                name,
+                DoctorCheckOutcome::Pass("read from command line argument or environment variable.".to_string()),
+            ),
+            Some(slack_user_oauth_token.clone()),
+        );
+    }
+
+    if let Some(slack_user_oauth_token_secret) = application_configuration_data.slack_user_oauth_token_secret.as_ref() {
+        let secret_source = SecretSource::parse(slack_user_oauth_token_secret);
+        return match application_configuration_getter.get_slack_user_oauth_token_from_secret(slack_user_oauth_token_secret)
+        {
+            Ok(slack_user_oauth_token) => (
+                DoctorCheck::new(
+                    name,
+                    DoctorCheckOutcome::Pass(format!("read from '{}' secret source.", secret_source.scheme)),
+                ),
+                Some(slack_user_oauth_token),
+            ),
+            Err(err) => (
+                DoctorCheck::new(
+                    name,
+                    DoctorCheckOutcome::Fail(
+                        err.to_string(),
+                        "Check the secret source, e.g. run the `cmd:` command yourself or look the secret up with `secret-tool lookup service <service> account <account>`. See `Secret sources` section of README."
+                            .to_string(),
+                    ),
+                ),
+                None,
+            ),
+        };
+    }
+
+    let slack_user_oauth_token_path = application_configuration_data
+        .slack_user_oauth_token_path
+        .clone()
+        .unwrap_or_default();
+    match application_configuration_getter.get_slack_user_oauth_token(&slack_user_oauth_token_path) {
+        Ok(slack_user_oauth_token) => (
+            DoctorCheck::new(
+                name,
+                DoctorCheckOutcome::Pass(format!("read from path: '{}'.", slack_user_oauth_token_path)),
+            ),
+            Some(slack_user_oauth_token.user_oauth_token),
+        ),
+        Err(err) => (
+            DoctorCheck::new(
+                name,
+                DoctorCheckOutcome::Fail(
+                    err.to_string(),
+                    format!(
+                        "Create the token json file as shown in `Slack User Oauth Token` section of README or set `{}` environment variable.",
+                        SLACK_USER_OAUTH_TOKEN_ENV_VAR
+                    ),
+                ),
+            ),
+            None,
+        ),
+    }
+}
+
+pub async fn check_slack_user_oauth_token(slack_api_client: &SlackApiClient) -> DoctorCheck {
+    let outcome = match slack_api_client
+        .validate_auth_token(SLACK_AUTH_TEST_ENDPOINT, &REQUIRED_SLACK_USER_OAUTH_SCOPES)
+        .await
+    {
+        Ok(auth_test_response) => DoctorCheckOutcome::Pass(format!(
+            "authenticated as user '{}' in workspace '{}' with scopes: {}.",
+            auth_test_response.body.user.unwrap_or_default(),
+            auth_test_response.body.team.unwrap_or_default(),
+            auth_test_response.scopes.join(", ")
+        )),
+        Err(err @ SlackApiError::InvalidAuthError) => DoctorCheckOutcome::Fail(
+            err.to_string(),
+            format!(
+                "Copy the `User OAuth Token` (starts with `xoxp`) from `Features -> OAuth & Permissions` of your Slack app. {}",
+                SLACK_DOCS_HINT
+            ),
+        ),
+        Err(err @ SlackApiError::MissingScopesError(_)) => DoctorCheckOutcome::Fail(
+            err.to_string(),
+            format!(
+                "The Slack app manifest must request user scopes: {}. {}",
+                REQUIRED_SLACK_USER_OAUTH_SCOPES.join(", "),
+                SLACK_DOCS_HINT
+            ),
+        ),
+        Err(err) => DoctorCheckOutcome::Fail(
+            err.to_string(),
+            "Check your network connection and that https://slack.com/api is reachable.".to_string(),
+        ),
+    };
+    DoctorCheck::new("Slack user oauth token", outcome)
+}
+
+// Without a status set by the application before, e.g. after install, a status left in Slack is kept as set manually.
+pub fn check_sync_state(state_path: &str) -> DoctorCheck {
+    let name = "Synchronization state";
+    let outcome = match SyncState::read(state_path) {
+        Ok(sync_state) if sync_state.replace_manual_status => DoctorCheckOutcome::Pass(format!(
+            "loaded from path: '{}'. Status set manually is replaced on the next synchronization.",
+            state_path
+        )),
+        Ok(SyncState {
+            last_synced_status: None,
+            ..
+        }) => DoctorCheckOutcome::Warn(
+            format!(
+                "no status has been synchronized yet at path: '{}'. A status already set in Slack is kept as set \
+                 manually until it is cleared or expires.",
+                state_path
+            ),
+            REPLACE_MANUAL_STATUS_HINT.to_string(),
+        ),
+        Ok(_) => DoctorCheckOutcome::Pass(format!("loaded from path: '{}'.", state_path)),
+        Err(err) => DoctorCheckOutcome::Fail(
+            err.to_string(),
+            "Remove the file to start over, pause and the last status set are lost.".to_string(),
+        ),
+    };
+    DoctorCheck::new(name, outcome)
+}
+
+fn check_logging_config(logging_config_path: &str) -> DoctorCheck {
+    let name = "Logging configuration";
+    if !Path::new(logging_config_path).exists() {
+        return DoctorCheck::new(
+            name,
+            DoctorCheckOutcome::Warn(
+                format!(
+                    "no file found at path: '{}'. Default logging configuration is used.",
+                    logging_config_path
+                ),
+                "Copy `config/logging_config.yaml` from the repository to customise logging.".to_string(),
+            ),
+        );
+    }
+    let outcome = match LoggerConfigurator::read_logging_config_from_file(logging_config_path) {
+        Ok(_) => DoctorCheckOutcome::Pass(format!("loaded from path: '{}'.", logging_config_path)),
+        Err(err) => DoctorCheckOutcome::Fail(
+            err.to_string(),
+            "See https://docs.rs/log4rs/latest/log4rs/#configuration-via-a-yaml-file for possible options.".to_string(),
+        ),
+    };
+    DoctorCheck::new(name, outcome)
+}
+
+// Without `TZ` set, e.g. in a container or a service, the system time zone is often UTC.
+pub fn check_local_time_zone(
+    time_zone: Option<&str>,
+    utc_offset: FixedOffset,
+    working_hours: Option<&WorkingHours>,
+) -> DoctorCheck {
+    let name = "Local time zone";
+    let time_zone_description = get_local_time_zone_description(time_zone, utc_offset);
+    let working_hours_time_zone = working_hours.and_then(|working_hours| working_hours.time_zone.as_deref());
+    let outcome = match (time_zone, working_hours, working_hours_time_zone) {
+        (_, Some(_), Some(working_hours_time_zone)) => DoctorCheckOutcome::Pass(format!(
+            "working hours use '{}', today's events use {}.",
+            working_hours_time_zone, time_zone_description
+        )),
+        (None, Some(_), None) => DoctorCheckOutcome::Warn(
+            format!(
+                "`{}` environment variable is not set, working hours and today's events use {}.",
+                TIME_ZONE_ENV_VAR, time_zone_description
+            ),
+            format!(
+                "Set `working_hours.time_zone` to your time zone, e.g. `Europe/Warsaw`, or set `{}` wherever the \
+                 application runs.",
+                TIME_ZONE_ENV_VAR
+            ),
+        ),
+        _ => DoctorCheckOutcome::Pass(format!("{}.", time_zone_description)),
+    };
+    DoctorCheck::new(name, outcome)
+}
+
+fn skip(name: &str, reason: &str) -> DoctorCheck {
+    DoctorCheck::new(name, DoctorCheckOutcome::Skip(reason.to_string()))
+}
+
+async fn run_checks(cli_application_configuration_data: ApplicationConfigurationData) -> Vec<DoctorCheck> {
+    run_checks_with_config_dirs(
+        cli_application_configuration_data,
+        Some(PathBuf::from(SYSTEM_CONFIG_DIR)),
+        get_user_config_dir(),
+    )
+    .await
+}
+
+pub async fn run_checks_with_config_dirs(
+    cli_application_configuration_data: ApplicationConfigurationData,
+    system_config_dir: Option<PathBuf>,
+    user_config_dir: Option<PathBuf>,
+) -> Vec<DoctorCheck> {
+    let application_config_path = cli_application_configuration_data
+        .application_config_path
+        .clone()
+        .unwrap_or(DEFAULT_APPLICATION_CONFIG_PATH.to_string());
+    let logging_config_path = cli_application_configuration_data
+        .logging_config_path
+        .clone()
+        .unwrap_or(DEFAULT_LOGGING_CONFIG_PATH.to_string());
+    let mut checks = vec![
+        check_config_dir_file("System configuration file", system_config_dir.as_deref()),
+        check_config_dir_file("User configuration file", user_config_dir.as_deref()),
+        check_application_config_file(
+            &application_config_path,
+            cli_application_configuration_data.application_config_format,
+        ),
+        check_command_line_arguments(&cli_application_configuration_data),
+    ];
+
+    match ApplicationConfigurationGetter::new_with_config_dirs(
+        cli_application_configuration_data,
+        system_config_dir,
+        user_config_dir,
+    ) {
+        Ok(application_configuration_getter) => {
+            checks.extend(run_application_configuration_checks(&application_configuration_getter).await);
+        }
+        // Values of the other configuration layers cannot be merged with a file which cannot be parsed, so the checks
+        // of the merged values do not run.
+        Err(err) => {
+            checks.push(DoctorCheck::new(
+                MERGED_CONFIGURATION_CHECK_NAME,
+                DoctorCheckOutcome::Fail(err.to_string(), APPLICATION_CONFIG_FILE_HINT.to_string()),
+            ));
+            checks.push(check_logging_config(&logging_config_path));
+        }
+    }
+    checks
+}
+
+// Checks of the configured values run also when the merged configuration is invalid, so all problems are reported at
+// once.
+pub async fn run_application_configuration_checks(
+    application_configuration_getter: &ApplicationConfigurationGetter,
+) -> Vec<DoctorCheck> {
+    let mut checks = Vec::new();
+    let application_configuration_data = match application_configuration_getter
+        .get_merged_application_configuration_data()
+    {
+        Ok(application_configuration_data) => {
+            checks.push(DoctorCheck::new(
+                MERGED_CONFIGURATION_CHECK_NAME,
+                DoctorCheckOutcome::Pass(format!(
+                    "all required values are set. Configuration layers: {}.",
+                    application_configuration_getter
+                        .get_configuration_layers()
+                        .iter()
+                        .map(|configuration_layer| match &configuration_layer.source {
+                            ConfigurationSource::File(path) => format!("{} '{}'", configuration_layer.name, path),
+                            _ => configuration_layer.name.clone(),
+                        })
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )),
+            ));
+            application_configuration_data
+        }
+        Err(err) => {
+            let hint = match err {
+                ConfigurationError::InvalidConfigurationValueError(_) => {
+                    "Fix the reported values where they come from: configuration file, environment variable or command line argument."
+                }
+                _ => "Set the missing values in the application configuration file or pass them as command line arguments.",
+            };
+            checks.push(DoctorCheck::new(
+                MERGED_CONFIGURATION_CHECK_NAME,
+                DoctorCheckOutcome::Fail(err.to_string(), hint.to_string()),
+            ));
+            application_configuration_getter
+                .get_application_configuration_data_with_sources()
+                .0
+        }
+    };
+    if let Some(unknown_fields_check) = check_unknown_fields(application_configuration_getter) {
+        checks.push(unknown_fields_check);
+    }
+
+    let (service_account_key_check, service_account_key) =
+        check_service_account_key(application_configuration_getter, &application_configuration_data);
+    checks.push(service_account_key_check);
+    match (
+        service_account_key,
+        application_configuration_data.calendar_id.as_deref(),
+    ) {
+        (Some(service_account_key), Some(calendar_id)) => {
+            checks.push(check_calendar_access(service_account_key, calendar_id).await)
+        }
+        (None, _) => checks.push(skip("Google Calendar access", "service account key is invalid.")),
+        (_, None) => checks.push(skip("Google Calendar access", "calendar_id is not set.")),
+    }
+
+    let (slack_user_oauth_token_source_check, slack_user_oauth_token) =
+        check_slack_user_oauth_token_source(application_configuration_getter, &application_configuration_data);
+    checks.push(slack_user_oauth_token_source_check);
+    match slack_user_oauth_token {
+        Some(slack_user_oauth_token) => {
+            let slack_api_client = SlackApiClient::new(
+                SLACK_API_BASE_URL.to_owned(),
+                slack_user_oauth_token.into_inner(),
+                Client::new(),
+            );
+            checks.push(check_slack_user_oauth_token(&slack_api_client).await);
+        }
+        None => checks.push(skip("Slack user oauth token", "token cannot be read.")),
+    }
+
+    checks.push(check_local_time_zone(
+        std::env::var(TIME_ZONE_ENV_VAR).ok().as_deref(),
+        *Local::now().offset(),
+        application_configuration_data.working_hours.as_ref(),
+    ));
+    checks.push(check_sync_state(&application_configuration_data.get_state_path()));
+    checks.push(check_logging_config(
+        application_configuration_data
+            .logging_config_path
+            .as_deref()
+            .unwrap_or(DEFAULT_LOGGING_CONFIG_PATH),
+    ));
+    checks
+}
+
+pub async fn run_doctor(cli_application_configuration_data: ApplicationConfigurationData) -> Result<()> {
+    let checks = run_checks(cli_application_configuration_data).await;
+    for check in checks.iter() {
+        check.print();
+    }
+
+    let failed_checks_count = checks
+        .iter()
+        .filter(|check| matches!(check.outcome, DoctorCheckOutcome::Fail(..)))
+        .count();
+    if failed_checks_count > 0 {
+        anyhow::bail!(
+            "{} check(s) failed. Fix the problems reported above and run doctor again.",
+            failed_checks_count
+        );
+    }
+    println!("All checks passed.");
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+// Allows synchronizations slowed down by rate limits or slow responses, before the daemon is reported as unhealthy.
+pub const HEALTH_GRACE_PERIOD: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Default)]
+struct Health {
+    scheduler_heartbeat_at: Option<Instant>,
+    configuration_loaded: bool,
+    google_access_token_at: Option<Instant>,
+    slack_auth_test_at: Option<Instant>,
+}
+
+// Updated by the daemon loop and synchronization, read by `/healthz` and `/readyz` endpoints served on another task.
+#[derive(Debug, Clone)]
+pub struct HealthState {
+    health: Arc<Mutex<Health>>,
+    max_age: Duration,
+}
+
+impl HealthState {
+    // Scheduler heartbeat and successful API checks are expected at least once per two synchronizations.
+    pub fn new(sync_interval: Duration) -> HealthState {
+        HealthState {
+            health: Arc::new(Mutex::new(Health::default())),
+            max_age: sync_interval * 2 + HEALTH_GRACE_PERIOD,
+        }
+    }
+
+    pub fn record_scheduler_heartbeat(&self) {
+        self.update(|health| health.scheduler_heartbeat_at = Some(Instant::now()));
+    }
+
+    pub fn set_configuration_loaded(&self, configuration_loaded: bool) {
+        self.update(|health| health.configuration_loaded = configuration_loaded);
+    }
+
+    pub fn record_google_access_token(&self) {
+        self.update(|health| health.google_access_token_at = Some(Instant::now()));
+    }
+
+    pub fn record_slack_auth_test(&self) {
+        self.update(|health| health.slack_auth_test_at = Some(Instant::now()));
+    }
+
+    pub fn get_liveness_problems(&self, now: Instant) -> Vec<String> {
+        let health = self.health.lock().unwrap_or_else(|err| err.into_inner());
+        match self.is_recent(health.scheduler_heartbeat_at, now) {
+            true => vec![],
+            false => vec![format!(
+                "scheduler loop did not run in the last {} seconds",
+                self.max_age.as_secs()
+            )],
+        }
+    }
+
+    pub fn get_readiness_problems(&self, now: Instant) -> Vec<String> {
+        let mut readiness_problems = self.get_liveness_problems(now);
+        let health = self.health.lock().unwrap_or_else(|err| err.into_inner());
+        if !health.configuration_loaded {
+            readiness_problems.push("application configuration is not loaded".to_string());
+        }
+        if !self.is_recent(health.google_access_token_at, now) {
+            readiness_problems.push(format!(
+                "google service account did not get an access token in the last {} seconds",
+                self.max_age.as_secs()
+            ));
+        }
+        if !self.is_recent(health.slack_auth_test_at, now) {
+            readiness_problems.push(format!(
+                "slack auth.test did not pass in the last {} seconds",
+                self.max_age.as_secs()
+            ));
+        }
+        readiness_problems
+    }
+
+    fn is_recent(&self, happened_at: Option<Instant>, now: Instant) -> bool {
+        happened_at.is_some_and(|happened_at| now.saturating_duration_since(happened_at) <= self.max_age)
+    }
+
+    fn update(&self, update: impl FnOnce(&mut Health)) {
+        update(&mut self.health.lock().unwrap_or_else(|err| err.into_inner()));
+    }
+}
@@ -0,0 +1,139 @@
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use log::{error, info};
+use metrics_exporter_prometheus::PrometheusHandle;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    time::timeout,
+};
+
+use crate::health::HealthState;
+
+pub const METRICS_PATH: &str = "/metrics";
+pub const HEALTHZ_PATH: &str = "/healthz";
+pub const READYZ_PATH: &str = "/readyz";
+pub const HTTP_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Endpoints served on one address. Metrics and health endpoints share the server when their addresses are the same.
+#[derive(Clone)]
+pub struct HttpRoutes {
+    pub prometheus_handle: Option<PrometheusHandle>,
+    pub health_state: Option<HealthState>,
+}
+
+impl HttpRoutes {
+    fn get_paths(&self) -> Vec<&'static str> {
+        let mut paths = vec![];
+        if self.prometheus_handle.is_some() {
+            paths.push(METRICS_PATH);
+        }
+        if self.health_state.is_some() {
+            paths.extend([HEALTHZ_PATH, READYZ_PATH]);
+        }
+        paths
+    }
+}
+
+struct HttpResponse {
+    status: &'static str,
+    content_type: &'static str,
+    body: String,
+}
+
+impl HttpResponse {
+    fn new(status: &'static str, body: String) -> HttpResponse {
+        HttpResponse {
+            status,
+            content_type: "text/plain",
+            body,
+        }
+    }
+
+    fn to_http(&self) -> String {
+        format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            self.content_type,
+            self.body.len(),
+            self.body
+        )
+    }
+}
+
+// Requests are answered on their own tasks, so probes and scrapes are answered while synchronization runs. Returns the
+// address listened on, e.g. the port chosen for port 0.
+pub async fn start_http_server(address: SocketAddr, http_routes: HttpRoutes) -> Result<SocketAddr> {
+    let listener = TcpListener::bind(address)
+        .await
+        .with_context(|| format!("Failed to listen for HTTP requests on '{}'.", address))?;
+    let local_address = listener.local_addr()?;
+    for path in http_routes.get_paths() {
+        info!("Serving: 'http://{}{}'.", local_address, path);
+    }
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let http_routes = http_routes.clone();
+                    tokio::spawn(async move {
+                        if let Err(err) = handle_http_connection(stream, &http_routes).await {
+                            error!("Failed to handle HTTP request: {:?}", err);
+                        }
+                    });
+                }
+                Err(err) => error!("Failed to accept HTTP connection: {}", err),
+            }
+        }
+    });
+    Ok(local_address)
+}
+
+async fn handle_http_connection(stream: TcpStream, http_routes: &HttpRoutes) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    let mut request_line = String::new();
+    timeout(HTTP_REQUEST_TIMEOUT, async {
+        reader.read_line(&mut request_line).await?;
+        // Headers are read to the end, so the client is not reset by closing the connection with unread data.
+        let mut header_line = String::new();
+        while reader.read_line(&mut header_line).await? > 2 {
+            header_line.clear();
+        }
+        Ok::<(), std::io::Error>(())
+    })
+    .await
+    .with_context(|| "Timed out waiting for HTTP request.")??;
+    let path = match request_line.split_whitespace().collect::<Vec<&str>>().as_slice() {
+        ["GET", path, ..] => path.to_string(),
+        _ => String::new(),
+    };
+    writer
+        .write_all(get_http_response(&path, http_routes).to_http().as_bytes())
+        .await?;
+    Ok(())
+}
+
+fn get_http_response(path: &str, http_routes: &HttpRoutes) -> HttpResponse {
+    match (path, &http_routes.prometheus_handle, &http_routes.health_state) {
+        (METRICS_PATH, Some(prometheus_handle), _) => HttpResponse {
+            status: "200 OK",
+            content_type: "text/plain; version=0.0.4",
+            body: prometheus_handle.render(),
+        },
+        (HEALTHZ_PATH, _, Some(health_state)) => get_probe_response(health_state.get_liveness_problems(Instant::now())),
+        (READYZ_PATH, _, Some(health_state)) => get_probe_response(health_state.get_readiness_problems(Instant::now())),
+        _ => HttpResponse::new("404 Not Found", "Not Found\n".to_string()),
+    }
+}
+
+fn get_probe_response(problems: Vec<String>) -> HttpResponse {
+    match problems.is_empty() {
+        true => HttpResponse::new("200 OK", "OK\n".to_string()),
+        false => HttpResponse::new("503 Service Unavailable", format!("{}\n", problems.join("\n"))),
+    }
+}
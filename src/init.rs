@@ -0,0 +1,305 @@
+use std::{
+    io::{stdin, stdout, BufRead, Write},
+    path::Path,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::ValueEnum;
+use configuration::{
+    read_configuration, read_json_configuration, validate_calendar_id, write_configuration, write_json_configuration,
+    ApplicationConfigurationData, ConfigurationFormat, Secret, SlackUserOauthToken, CONFIGURATION_FILE_PERMISSIONS,
+    DEFAULT_APPLICATION_CONFIG_PATH, DEFAULT_LOGGING_CONFIG_PATH, DEFAULT_SERVICE_ACCOUNT_PATH,
+    DEFAULT_SLACK_USER_OAUTH_TOKEN_PATH, SECRET_FILE_PERMISSIONS, SLACK_USER_OAUTH_TOKEN_ENV_VAR,
+};
+use google_calendar3::yup_oauth2::ServiceAccountKey;
+
+use crate::cli::{InitArgs, SlackUserOauthTokenSource};
+
+const SLACK_USER_OAUTH_TOKEN_PREFIX: &str = "xoxp-";
+
+// Secrets typed into a terminal are hidden, answers read from other input are read like other answers.
+struct Prompter<R: BufRead> {
+    interactive: bool,
+    input: R,
+    hide_secrets: bool,
+}
+
+impl<R: BufRead> Prompter<R> {
+    fn read_answer(&mut self, question: &str, default: Option<&str>) -> Result<String> {
+        match default {
+            Some(default) => print!("{} [{}]: ", question, default),
+            None => print!("{}: ", question),
+        }
+        stdout().flush()?;
+        let mut answer = String::new();
+        if self.input.read_line(&mut answer)? == 0 {
+            bail!("No answer given for: '{}'.", question);
+        }
+        let answer = answer.trim();
+        Ok(match (answer.is_empty(), default) {
+            (true, Some(default)) => default.to_string(),
+            _ => answer.to_string(),
+        })
+    }
+
+    // Values given on command line are used without asking. Without a terminal we fall back to defaults. Answers are
+    // asked again until they pass validation, while invalid command line values are reported as errors.
+    fn ask<T>(
+        &mut self,
+        question: &str,
+        flag: &str,
+        value: Option<String>,
+        default: Option<&str>,
+        validate: impl Fn(&str) -> Result<T>,
+    ) -> Result<(String, T)> {
+        if let Some(value) = value {
+            let validated = validate(&value).with_context(|| format!("Invalid value of --{}.", flag))?;
+            return Ok((value, validated));
+        }
+        if !self.interactive {
+            return match default {
+                Some(default) => {
+                    let validated = validate(default)
+                        .with_context(|| format!("Invalid default value of --{}: '{}'.", flag, default))?;
+                    Ok((default.to_string(), validated))
+                }
+                None => bail!("Missing value of --{} required in non-interactive mode.", flag),
+            };
+        }
+        loop {
+            let answer = self.read_answer(question, default)?;
+            match validate(&answer) {
+                Ok(validated) => return Ok((answer, validated)),
+                Err(err) => println!("Invalid answer: {:#}", err),
+            }
+        }
+    }
+
+    fn read_secret(&mut self, question: &str) -> Result<String> {
+        if self.hide_secrets {
+            return Ok(rpassword::prompt_password(format!("{} (input is hidden): ", question))?);
+        }
+        self.read_answer(question, None)
+    }
+
+    fn ask_secret(&mut self, question: &str, flag: &str, value: Option<String>) -> Result<String> {
+        let validate = |answer: &str| {
+            if answer.starts_with(SLACK_USER_OAUTH_TOKEN_PREFIX) {
+                Ok(())
+            } else {
+                bail!(
+                    "slack user oauth token must start with '{}'.",
+                    SLACK_USER_OAUTH_TOKEN_PREFIX
+                )
+            }
+        };
+        if let Some(value) = value {
+            validate(&value).with_context(|| format!("Invalid value of --{}.", flag))?;
+            return Ok(value);
+        }
+        if !self.interactive {
+            bail!("Missing value of --{} required in non-interactive mode.", flag);
+        }
+        loop {
+            let answer = self.read_secret(question)?;
+            match validate(answer.trim()) {
+                Ok(()) => return Ok(answer.trim().to_string()),
+                Err(err) => println!("Invalid answer: {:#}", err),
+            }
+        }
+    }
+
+    fn confirm_overwrite(&mut self, path: &str, force: bool) -> Result<()> {
+        if force || !Path::new(path).exists() {
+            return Ok(());
+        }
+        if !self.interactive {
+            bail!("File '{}' already exists. Use --force to overwrite it.", path);
+        }
+        let answer = self.read_answer(&format!("File '{}' already exists. Overwrite it? [y/N]", path), None)?;
+        if answer.eq_ignore_ascii_case("y") {
+            Ok(())
+        } else {
+            bail!("Not overwriting existing file '{}'.", path)
+        }
+    }
+}
+
+fn validate_not_empty(answer: &str) -> Result<()> {
+    if answer.is_empty() {
+        bail!("value cannot be empty.")
+    }
+    Ok(())
+}
+
+fn validate_calendar_id_answer(answer: &str) -> Result<()> {
+    validate_calendar_id(answer).map_err(|err| anyhow!("{}.", err))
+}
+
+fn validate_service_account_key(answer: &str) -> Result<ServiceAccountKey> {
+    Ok(read_json_configuration::<ServiceAccountKey>(answer)?)
+}
+
+fn validate_slack_user_oauth_token_source(answer: &str) -> Result<SlackUserOauthTokenSource> {
+    SlackUserOauthTokenSource::from_str(answer, true)
+        .map_err(|_| anyhow!("token source must be either 'file' or 'env'."))
+}
+
+pub fn run_init(init_args: InitArgs) -> Result<()> {
+    run_init_with_input(init_args, stdin().lock(), true)
+}
+
+pub fn run_init_with_input(init_args: InitArgs, input: impl BufRead, hide_secrets: bool) -> Result<()> {
+    let mut prompter = Prompter {
+        interactive: !init_args.non_interactive,
+        input,
+        hide_secrets,
+    };
+
+    let (application_config_path, _) = prompter.ask(
+        "Path of the application configuration file to create",
+        "application-config-path",
+        init_args.application_config_path,
+        Some(DEFAULT_APPLICATION_CONFIG_PATH),
+        validate_not_empty,
+    )?;
+    prompter.confirm_overwrite(&application_config_path, init_args.force)?;
+
+    let (calendar_id, _) = prompter.ask(
+        "Google calendar id (see `Integrate calendar` section of the calendar settings)",
+        "calendar-id",
+        init_args.calendar_id,
+        None,
+        validate_calendar_id_answer,
+    )?;
+
+    let (service_account_key_path, _) = prompter.ask(
+        "Path where the service account key is stored",
+        "service-account-key-path",
+        init_args.service_account_key_path,
+        Some(DEFAULT_SERVICE_ACCOUNT_PATH),
+        validate_not_empty,
+    )?;
+    let (downloaded_service_account_key_path, service_account_key) = prompter.ask(
+        "Path to the service account key json file downloaded from Google Cloud",
+        "downloaded-service-account-key-path",
+        init_args.downloaded_service_account_key_path,
+        Some(service_account_key_path.as_str()),
+        validate_service_account_key,
+    )?;
+
+    let (_, slack_user_oauth_token_source) = prompter.ask(
+        &format!(
+            "Read slack user oauth token from 'file' or 'env' ({} environment variable)",
+            SLACK_USER_OAUTH_TOKEN_ENV_VAR
+        ),
+        "slack-user-oauth-token-source",
+        init_args
+            .slack_user_oauth_token_source
+            .and_then(|source| source.to_possible_value())
+            .map(|source| source.get_name().to_string()),
+        Some("file"),
+        validate_slack_user_oauth_token_source,
+    )?;
+    let slack_user_oauth_token = match slack_user_oauth_token_source {
+        SlackUserOauthTokenSource::File => {
+            let (slack_user_oauth_token_path, _) = prompter.ask(
+                "Path where the slack user oauth token is stored",
+                "slack-user-oauth-token-path",
+                init_args.slack_user_oauth_token_path,
+                Some(DEFAULT_SLACK_USER_OAUTH_TOKEN_PATH),
+                validate_not_empty,
+            )?;
+            prompter.confirm_overwrite(&slack_user_oauth_token_path, init_args.force)?;
+            let user_oauth_token = prompter.ask_secret(
+                "Slack user oauth token",
+                "slack-user-oauth-token",
+                init_args.slack_user_oauth_token,
+            )?;
+            Some((
+                slack_user_oauth_token_path,
+                SlackUserOauthToken {
+                    user_oauth_token: Secret::new(user_oauth_token),
+                },
+            ))
+        }
+        SlackUserOauthTokenSource::Env => None,
+    };
+
+    let (logging_config_path, _) = prompter.ask(
+        "Path to the logging configuration file",
+        "logging-config-path",
+        init_args.logging_config_path,
+        Some(DEFAULT_LOGGING_CONFIG_PATH),
+        validate_not_empty,
+    )?;
+
+    if downloaded_service_account_key_path != service_account_key_path {
+        prompter.confirm_overwrite(&service_account_key_path, init_args.force)?;
+        write_json_configuration(&service_account_key_path, &service_account_key, SECRET_FILE_PERMISSIONS)?;
+        read_json_configuration::<ServiceAccountKey>(&service_account_key_path)?;
+        println!("Saved service account key at path: '{}'.", service_account_key_path);
+    }
+
+    let slack_user_oauth_token_path = match slack_user_oauth_token {
+        Some((slack_user_oauth_token_path, slack_user_oauth_token)) => {
+            write_json_configuration(
+                &slack_user_oauth_token_path,
+                &slack_user_oauth_token,
+                SECRET_FILE_PERMISSIONS,
+            )?;
+            read_json_configuration::<SlackUserOauthToken>(&slack_user_oauth_token_path)?;
+            println!(
+                "Saved slack user oauth token at path: '{}'.",
+                slack_user_oauth_token_path
+            );
+            Some(slack_user_oauth_token_path)
+        }
+        None => None,
+    };
+
+    let application_configuration_data = ApplicationConfigurationData {
+        calendar_id: Some(calendar_id),
+        service_account_key_path: Some(service_account_key_path),
+        service_account_key: None,
+        service_account_key_secret: None,
+        slack_user_oauth_token_path,
+        slack_user_oauth_token: None,
+        slack_user_oauth_token_secret: None,
+        logging_config_path: Some(logging_config_path),
+        ..ApplicationConfigurationData::empty()
+    };
+    // Configuration is read back the same way the application reads it, so a file it cannot parse is not left behind.
+    let application_config_format = init_args
+        .application_config_format
+        .unwrap_or(ConfigurationFormat::from_path(&application_config_path));
+    write_configuration(
+        &application_config_path,
+        &application_configuration_data,
+        application_config_format,
+        CONFIGURATION_FILE_PERMISSIONS,
+    )?;
+    read_configuration::<ApplicationConfigurationData>(&application_config_path, application_config_format)?;
+    println!(
+        "Saved application configuration at path: '{}' as {:?}.",
+        application_config_path, application_config_format
+    );
+    if application_config_format != ConfigurationFormat::from_path(&application_config_path) {
+        println!(
+            "Remember to pass --application-config-format {} when running the application.",
+            application_config_format
+                .to_possible_value()
+                .map(|format| format.get_name().to_string())
+                .unwrap_or_default()
+        );
+    }
+
+    if slack_user_oauth_token_source == SlackUserOauthTokenSource::Env {
+        println!(
+            "Remember to set {} environment variable before running the application.",
+            SLACK_USER_OAUTH_TOKEN_ENV_VAR
+        );
+    }
+    println!("Run `slack-gcal-sync doctor` to verify the setup.");
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use anyhow::Result;
+
+use crate::{
+    admin::{get_admin_socket_path, send_admin_command},
+    cli::{LogLevelArgs, LogLevelCommand},
+};
+
+pub fn run_log_level(log_level_args: LogLevelArgs) -> Result<()> {
+    let admin_socket_path = get_admin_socket_path(log_level_args.admin_socket_path.as_deref());
+    let command = get_admin_command(log_level_args.command);
+    println!("{}", send_admin_command(&admin_socket_path, &command)?);
+    Ok(())
+}
+
+// Duration is sent in milliseconds, as formatted durations may contain spaces and whole seconds would turn durations under
+// a second into zero.
+pub fn get_admin_command(log_level_command: LogLevelCommand) -> String {
+    match log_level_command {
+        LogLevelCommand::Set(log_level_set_args) => format!(
+            "log-level set {} {}ms",
+            log_level_set_args.directive,
+            log_level_set_args.duration.as_millis()
+        ),
+        LogLevelCommand::Reset => "log-level reset".to_string(),
+        LogLevelCommand::Show => "log-level show".to_string(),
+    }
+}
@@ -1,108 +1,431 @@
-use std::path::Path;
+mod admin;
+mod audit;
+mod cli;
+mod config;
+mod daemon;
+mod doctor;
+mod health;
+mod http_server;
+mod init;
+mod log_level;
+mod otlp;
+mod pause;
+mod status;
+mod sync_metrics;
+mod tests;
+mod timeline;
+
+use std::{
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 use anyhow::{Context, Result};
-use chrono::Utc;
-use clap::Parser;
-use configuration::{ApplicationConfiguration, ApplicationConfigurationData, ApplicationConfigurationGetter};
-use gcal_integration::{get_calendar_events_for_today, get_calendar_hub, get_service_account_authenticator};
-use log::{debug, info};
-use logging::LoggerConfigurator;
+use chrono::{DateTime, Duration, Utc};
+use clap::{CommandFactory, FromArgMatches};
+use cli::{Cli, Command};
+use configuration::{
+    ApplicationConfiguration, ApplicationConfigurationData, ApplicationConfigurationGetter, LogConsoleFormat,
+    SyncState, SyncedStatus, BUILT_IN_LOGGING_FIELD_NAMES,
+};
+use gcal_integration::{
+    check_service_account_access_token, get_calendar_events_for_today, get_calendar_hub,
+    get_service_account_authenticator,
+};
+use health::HealthState;
+use log::{debug, error, info, warn, LevelFilter};
+use logging::{
+    redact_secrets, AuditLog, AuditRecord, AuditStatus, ConsoleLogFormat, LogLevelController, LoggerConfigurator, Span,
+};
+use pause::REPLACE_MANUAL_STATUS_HINT;
 use reqwest::Client;
 use slack_integration::{
-    ProfileData, ProfileRequestBody, SlackApiClient, SLACK_API_BASE_URL, SLACK_USER_PROFILE_GET_ENDPOINT,
-    SLACK_USER_PROFILE_SET_ENDPOINT,
+    ProfileData, ProfileRequestBody, ProfileResponseBody, SlackApiClient, REQUIRED_SLACK_USER_OAUTH_SCOPES,
+    SLACK_API_BASE_URL, SLACK_AUTH_TEST_ENDPOINT, SLACK_USER_PROFILE_GET_ENDPOINT, SLACK_USER_PROFILE_SET_ENDPOINT,
+};
+use status::{get_status_change, get_status_timeline, StatusChange};
+use sync_metrics::{
+    observe_api_call, record_calendar_events_fetched, record_status_update, record_successful_sync,
+    GOOGLE_ACCESS_TOKEN_METHOD, GOOGLE_API, GOOGLE_EVENTS_LIST_METHOD, SLACK_API, STATUS_UPDATE_APPLIED,
+    STATUS_UPDATE_MANUAL_STATUS, STATUS_UPDATE_PAUSED, STATUS_UPDATE_SKIPPED,
 };
 
-fn configure_application() -> Result<ApplicationConfiguration> {
-    let mut logger_configurator = LoggerConfigurator::default();
-    let logging_handle = logger_configurator.setup_default_logger();
-    debug!("Default logging setup complete.");
+static FIRST_RUN_MANUAL_STATUS_WARNED: AtomicBool = AtomicBool::new(false);
 
-    debug!("Parsing command line arguments.");
-    let cli_application_configuration_data = ApplicationConfigurationData::parse();
-    debug!("Successfully parsed command line arguments.");
+fn configure_application(
+    cli_application_configuration_data: ApplicationConfigurationData,
+) -> Result<ApplicationConfiguration> {
+    setup_logging(&cli_application_configuration_data)?;
 
     info!("Loading application configuration.");
     let application_configuration_getter = ApplicationConfigurationGetter::new(cli_application_configuration_data);
     let application_configuration = application_configuration_getter?.get_application_configuration()?;
     info!("Successfully loaded application configuration.");
+    Ok(application_configuration)
+}
 
-    if Path::new(application_configuration.logging_config_path.as_str()).exists() {
-        info!(
-            "Loading logging configuration from path: '{}'",
-            application_configuration.logging_config_path
-        );
-        logger_configurator
-            .apply_logging_config_from_file(application_configuration.logging_config_path.as_str(), &logging_handle)?;
-        info!("Successfully loaded logging configuration.");
-    } else {
-        info!(
+// Logging configuration path is resolved before the logger is initialised, so the logger is set up once with the final
+// configuration and messages about loading the application configuration already go to the configured appenders.
+fn setup_logging(cli_application_configuration_data: &ApplicationConfigurationData) -> Result<LogLevelController> {
+    let application_configuration_data =
+        ApplicationConfigurationGetter::new(cli_application_configuration_data.clone())
+            .map(|application_configuration_getter| {
+                application_configuration_getter
+                    .get_application_configuration_data_with_sources()
+                    .0
+            })
+            .unwrap_or_else(|_| cli_application_configuration_data.clone());
+    let logging_config_path = application_configuration_data.logging_config_path.clone();
+    let logging_config_file_path = logging_config_path
+        .as_deref()
+        .filter(|logging_config_path| Path::new(logging_config_path).exists());
+
+    let logger_configurator = get_logger_configurator(&application_configuration_data);
+    let logging_handle = logger_configurator
+        .setup_logger(logging_config_file_path)
+        .with_context(|| "Failed to set up logging.")?;
+    match (logging_config_file_path, logging_config_path.as_deref()) {
+        (Some(logging_config_path), _) => info!("Loaded logging configuration from path: '{}'.", logging_config_path),
+        (None, Some(logging_config_path)) => info!(
             "No logging configuration found at path: '{}'. Using default.",
-            application_configuration.logging_config_path
-        );
+            logging_config_path
+        ),
+        (None, None) => info!("Using default logging configuration."),
+    }
+    if logging_config_file_path.is_some() {
+        let ignored_fields: Vec<&str> = application_configuration_data
+            .get_field_values()
+            .into_iter()
+            .filter(|(field, value)| BUILT_IN_LOGGING_FIELD_NAMES.contains(field) && value.is_some())
+            .map(|(field, _)| field)
+            .collect();
+        if !ignored_fields.is_empty() {
+            warn!(
+                "Logging configuration file is used, ignoring: {}. Only log_level applies on top of the file.",
+                ignored_fields.join(", ")
+            );
+        }
+    }
+    Ok(LogLevelController::new(
+        logging_handle,
+        logger_configurator,
+        logging_config_file_path.map(str::to_string),
+    ))
+}
+
+fn get_logger_configurator(application_configuration_data: &ApplicationConfigurationData) -> LoggerConfigurator {
+    let default_logger_configurator = match &application_configuration_data.log_dir {
+        Some(log_dir) => LoggerConfigurator::default().with_log_dir(log_dir),
+        None => LoggerConfigurator::default(),
+    };
+    LoggerConfigurator {
+        log_file_size: application_configuration_data
+            .log_file_size
+            .or(default_logger_configurator.log_file_size),
+        log_file_archive_count: application_configuration_data
+            .log_file_archive_count
+            .or(default_logger_configurator.log_file_archive_count),
+        log_level: application_configuration_data.log_level.map(LevelFilter::from),
+        console_log_format: application_configuration_data
+            .log_console_format
+            .map(|log_console_format| match log_console_format {
+                LogConsoleFormat::Pretty => ConsoleLogFormat::Pretty,
+                LogConsoleFormat::Json => ConsoleLogFormat::Json,
+                LogConsoleFormat::Compact => ConsoleLogFormat::Compact,
+            })
+            .or(default_logger_configurator.console_log_format),
+        log_to_file: application_configuration_data
+            .log_to_file
+            .or(default_logger_configurator.log_to_file),
+        ..default_logger_configurator
     }
-    Ok(application_configuration)
 }
 
 async fn run() -> Result<()> {
+    let arg_matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&arg_matches).unwrap_or_else(|err| err.exit());
+    match cli.command {
+        Some(Command::Doctor) => doctor::run_doctor(cli.application_configuration_data).await,
+        Some(Command::Init(init_args)) => init::run_init(init_args),
+        Some(Command::Config(config_args)) => config::run_config(
+            config_args,
+            cli.application_configuration_data,
+            ApplicationConfigurationData::get_cli_configuration_sources(&arg_matches),
+        ),
+        Some(Command::Daemon(daemon_args)) => {
+            daemon::run_daemon(
+                daemon_args,
+                cli.application_configuration_data,
+                ApplicationConfigurationData::get_cli_configuration_sources(&arg_matches),
+            )
+            .await
+        }
+        Some(Command::LogLevel(log_level_args)) => log_level::run_log_level(log_level_args),
+        Some(Command::Audit(audit_args)) => audit::run_audit(audit_args, cli.application_configuration_data),
+        Some(Command::Pause(pause_args)) => pause::run_pause(pause_args, cli.application_configuration_data),
+        Some(Command::Resume(resume_args)) => pause::run_resume(resume_args, cli.application_configuration_data),
+        Some(Command::Timeline) => timeline::run_timeline(cli.application_configuration_data).await,
+        None => {
+            let application_configuration = configure_application(cli.application_configuration_data)
+                .with_context(|| "Failed to configure application.")?;
+            sync(&application_configuration, None).await?;
+            Ok(())
+        }
+    }
+}
+
+// Every synchronization is a cycle with its own id, logged with each message written during it. Returns the instant of
+// the next planned status transition, when the status has to be resolved again.
+async fn sync(
+    application_configuration: &ApplicationConfiguration,
+    health_state: Option<&HealthState>,
+) -> Result<Option<DateTime<Utc>>> {
+    let mut cycle_span = Span::start_cycle("sync_cycle");
+    info!("Starting synchronization cycle: '{}'.", cycle_span.get_trace_id());
+    let sync_result = sync_calendar(application_configuration, health_state).await;
+    if let Err(err) = &sync_result {
+        cycle_span.set_error(redact_secrets(&err.to_string()));
+    }
+    sync_result
+}
+
+// Health state is updated by the daemon only, so its readiness probe reflects results of API checks.
+async fn sync_calendar(
+    application_configuration: &ApplicationConfiguration,
+    health_state: Option<&HealthState>,
+) -> Result<Option<DateTime<Utc>>> {
     let google_api_error_context = || "Failed to integrate with Google API using supplied service account key.";
-    let application_configuration = configure_application().with_context(|| "Failed to configure application.")?;
+
+    let slack_api_client = SlackApiClient::new(
+        SLACK_API_BASE_URL.to_owned(),
+        application_configuration.slack_user_oauth_token.expose_secret().clone(),
+        Client::new(),
+    );
+    info!("Validating Slack user oauth token.");
+    let auth_test_response = observe_api_call(
+        SLACK_API,
+        SLACK_AUTH_TEST_ENDPOINT,
+        &[],
+        slack_api_client.validate_auth_token(SLACK_AUTH_TEST_ENDPOINT, &REQUIRED_SLACK_USER_OAUTH_SCOPES),
+    )
+    .await
+    .with_context(|| "Failed to validate Slack user oauth token.")?;
+    if let Some(health_state) = health_state {
+        health_state.record_slack_auth_test();
+    }
+    let slack_user = auth_test_response.body.user.unwrap_or_default();
+    let mut user_span = Span::start("user");
+    user_span.set_attribute("user", &slack_user);
+    info!(
+        "Successfully validated Slack user oauth token for user: '{}'.",
+        slack_user
+    );
+
     let service_account_authenticator =
-        get_service_account_authenticator(application_configuration.service_account_key)
+        get_service_account_authenticator(application_configuration.service_account_key.expose_secret().clone())
             .await
             .with_context(google_api_error_context)?;
+    observe_api_call(
+        GOOGLE_API,
+        GOOGLE_ACCESS_TOKEN_METHOD,
+        &[],
+        check_service_account_access_token(&service_account_authenticator),
+    )
+    .await
+    .with_context(google_api_error_context)?;
+    if let Some(health_state) = health_state {
+        health_state.record_google_access_token();
+    }
     let hub = get_calendar_hub(service_account_authenticator).with_context(google_api_error_context)?;
 
     info!("Fetching google calendar events for today.");
-    let events = get_calendar_events_for_today(hub, application_configuration.calendar_id.as_str())
-        .await
-        .with_context(google_api_error_context)?;
+    let calendar_id = application_configuration.calendar_id.as_str();
+    let events = observe_api_call(
+        GOOGLE_API,
+        GOOGLE_EVENTS_LIST_METHOD,
+        &[("calendar_id", calendar_id)],
+        get_calendar_events_for_today(hub, calendar_id),
+    )
+    .await
+    .with_context(google_api_error_context)?;
+    record_calendar_events_fetched(calendar_id, events.len());
     info!(
         "Successfully fetched {} google calendar events for today.",
         events.len()
     );
 
-    for event in events {
-        println!("event summary: {:#?}", event.summary);
-        println!("event start time: {:#?}", event.start);
-        println!("event end time: {:#?}", event.end);
-        println!("\n");
+    for event in &events {
+        debug!(
+            "Event: '{}', start: {:?}, end: {:?}.",
+            event.summary.as_deref().unwrap_or_default(),
+            event.start,
+            event.end
+        );
     }
-    // Testing reading Slack user profile
-    let slack_api_client = SlackApiClient::new(
-        SLACK_API_BASE_URL.to_owned(),
-        application_configuration.slack_user_oauth_token,
-        Client::new(),
+    let now = Utc::now();
+    match get_status_change(&events, application_configuration, now) {
+        Some(status_change) => {
+            update_slack_status(&slack_api_client, &slack_user, status_change, application_configuration).await?
+        }
+        None => {
+            info!("No status rule matches ongoing events and no working hours are set, keeping the Slack user status.")
+        }
+    }
+    record_successful_sync(&slack_user, Utc::now().timestamp());
+
+    let next_status_transition = get_status_timeline(&events, application_configuration, now, now + Duration::days(1))
+        .into_iter()
+        .map(|status_transition| status_transition.at)
+        .find(|at| *at > now);
+    Ok(next_status_transition)
+}
+
+// Status is not changed while synchronization is paused or the user has set another status manually. Every status change
+// is appended to the audit log. Failing to write it does not stop the synchronization, as the status is already set.
+async fn update_slack_status(
+    slack_api_client: &SlackApiClient,
+    slack_user: &str,
+    status_change: StatusChange,
+    application_configuration: &ApplicationConfiguration,
+) -> Result<()> {
+    let state_path = application_configuration.state_path.as_str();
+    let sync_state = SyncState::read(state_path).with_context(|| "Failed to read synchronization state.")?;
+    let now = Utc::now();
+    if let Some(pause) = sync_state.get_active_pause(now) {
+        match pause.paused_until {
+            Some(paused_until) => info!(
+                "Synchronization is paused until {}, skipping the update.",
+                paused_until.to_rfc3339()
+            ),
+            None => info!("Synchronization is paused until resumed, skipping the update."),
+        }
+        record_status_update(STATUS_UPDATE_PAUSED);
+        return Ok(());
+    }
+
+    let slack_user_profile = observe_api_call(
+        SLACK_API,
+        SLACK_USER_PROFILE_GET_ENDPOINT,
+        &[],
+        slack_api_client.get_user_profile(SLACK_USER_PROFILE_GET_ENDPOINT),
+    )
+    .await?;
+    info!(
+        "Previous Slack user status: {}",
+        get_status_description(&slack_user_profile.body)
     );
-    let slack_user_profile = slack_api_client
-        .get_user_profile(SLACK_USER_PROFILE_GET_ENDPOINT)
-        .await?;
-    println!("Previous Slack user profile: {:#?}", slack_user_profile);
-    println!("Status: {}", slack_user_profile.status);
-    println!("Url: {}", slack_user_profile.url);
-    println!("OK: {}", slack_user_profile.body.ok);
-    println!("Profile: {:?}", slack_user_profile.body.profile);
-
-    // Testing setting Slack user profile
-    let profile_data = ProfileData {
-        status_text: format!("testing rust messaging on: {}", Utc::now()),
-        status_emoji: ":mountain_railway:".to_string(),
-        status_expiration: 0,
+
+    if let Some(profile) = slack_user_profile.body.profile.as_ref() {
+        if sync_state.is_manual_status(&get_synced_status(profile), now) {
+            if is_first_run_manual_status_warning_due(&sync_state, &FIRST_RUN_MANUAL_STATUS_WARNED) {
+                warn!(
+                    "Slack user status was set before the first synchronization, so it is kept as set manually until \
+                     it is cleared or expires. {}",
+                    REPLACE_MANUAL_STATUS_HINT
+                );
+            }
+            info!("Slack user status was set manually, skipping the update until it is cleared or expires.");
+            record_status_update(STATUS_UPDATE_MANUAL_STATUS);
+            return Ok(());
+        }
+    }
+    if slack_user_profile
+        .body
+        .profile
+        .as_ref()
+        .is_some_and(|profile| is_status_already_set(profile, &status_change.profile_data))
+    {
+        info!("Slack user status is already set, skipping the update.");
+        record_status_update(STATUS_UPDATE_SKIPPED);
+        if sync_state.replace_manual_status {
+            let mut sync_state =
+                SyncState::read(state_path).with_context(|| "Failed to read synchronization state.")?;
+            sync_state.last_synced_status = Some(get_synced_status(&status_change.profile_data));
+            sync_state.replace_manual_status = false;
+            sync_state
+                .write(state_path)
+                .with_context(|| "Failed to save synchronization state.")?;
+        }
+        return Ok(());
+    }
+    let audit_record = AuditRecord {
+        timestamp: Utc::now(),
+        user: slack_user.to_string(),
+        previous_status: slack_user_profile.body.profile.as_ref().map(get_audit_status),
+        new_status: get_audit_status(&status_change.profile_data),
+        event_id: status_change.event_id,
+        rule: Some(status_change.rule),
     };
+    let profile_request_body = ProfileRequestBody {
+        profile: status_change.profile_data,
+    };
+    let slack_new_user_profile = observe_api_call(
+        SLACK_API,
+        SLACK_USER_PROFILE_SET_ENDPOINT,
+        &[],
+        slack_api_client.set_user_profile(SLACK_USER_PROFILE_SET_ENDPOINT, &profile_request_body),
+    )
+    .await?;
+    record_status_update(STATUS_UPDATE_APPLIED);
+    info!(
+        "New Slack user status: {}",
+        get_status_description(&slack_new_user_profile.body)
+    );
+    if let Err(err) = AuditLog::new(&application_configuration.audit_log_path).append(&audit_record) {
+        error!("Failed to record status change in audit log. {}", err);
+    }
+    // State is read again, so a pause set in the meantime is kept.
+    let mut sync_state = SyncState::read(state_path).with_context(|| "Failed to read synchronization state.")?;
+    sync_state.last_synced_status = Some(get_synced_status(&profile_request_body.profile));
+    sync_state.replace_manual_status = false;
+    sync_state
+        .write(state_path)
+        .with_context(|| "Failed to save synchronization state.")
+}
+
+// Without a status set by the application before, e.g. after install, a status left in Slack blocks every update. It is
+// logged as a warning with the remedy once per process, as every synchronization would repeat it.
+fn is_first_run_manual_status_warning_due(sync_state: &SyncState, warned: &AtomicBool) -> bool {
+    sync_state.last_synced_status.is_none() && !warned.swap(true, Ordering::Relaxed)
+}
 
-    let profile_request_body = ProfileRequestBody { profile: profile_data };
-    let slack_new_user_profile = slack_api_client
-        .set_user_profile(SLACK_USER_PROFILE_SET_ENDPOINT, &profile_request_body)
-        .await?;
-    println!("New Slack user profile: {:#?}", slack_new_user_profile);
+// Custom profile fields are not read back from Slack, so only the status is compared. Back to back events with the same
+// status differ in expiration, which replaces their join links too.
+fn is_status_already_set(current_profile_data: &ProfileData, new_profile_data: &ProfileData) -> bool {
+    current_profile_data.status_text == new_profile_data.status_text
+        && current_profile_data.status_emoji == new_profile_data.status_emoji
+        && current_profile_data.status_expiration == new_profile_data.status_expiration
+}
 
-    Ok(())
+fn get_synced_status(profile_data: &ProfileData) -> SyncedStatus {
+    SyncedStatus {
+        status_text: profile_data.status_text.clone(),
+        status_emoji: profile_data.status_emoji.clone(),
+        status_expiration: profile_data.status_expiration,
+    }
+}
+
+fn get_audit_status(profile_data: &ProfileData) -> AuditStatus {
+    AuditStatus {
+        status_text: profile_data.status_text.clone(),
+        status_emoji: profile_data.status_emoji.clone(),
+        status_expiration: profile_data.status_expiration,
+    }
+}
+
+// Only the status is logged, the whole profile response is not needed and may contain personal data.
+fn get_status_description(profile_response_body: &ProfileResponseBody) -> String {
+    match (&profile_response_body.profile, &profile_response_body.error) {
+        (Some(profile), _) => format!("'{} {}'.", profile.status_emoji, profile.status_text),
+        (None, Some(error)) => format!("unknown, Slack API error: '{}'.", error),
+        (None, None) => "unknown.".to_string(),
+    }
 }
 
 #[tokio::main]
 async fn main() {
     if let Err(err) = run().await {
-        eprintln!("Application Error:\n{:?}", err);
+        eprintln!("Application Error:\n{}", redact_secrets(&format!("{:?}", err)));
         std::process::exit(1);
     }
 }
@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use log::{debug, warn};
+use logging::{enable_span_export, get_otlp_traces_request, take_finished_spans};
+use reqwest::{header::CONTENT_TYPE, Client};
+
+pub const OTLP_TRACES_PATH: &str = "/v1/traces";
+pub const OTLP_EXPORT_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Sends spans of finished sync cycles to an OpenTelemetry collector over OTLP/HTTP with JSON encoding.
+#[derive(Clone)]
+pub struct OtlpExporter {
+    traces_url: String,
+    client: Client,
+}
+
+impl OtlpExporter {
+    pub fn new(otlp_endpoint: &str) -> OtlpExporter {
+        enable_span_export();
+        OtlpExporter {
+            traces_url: format!("{}{}", otlp_endpoint.trim_end_matches('/'), OTLP_TRACES_PATH),
+            client: Client::new(),
+        }
+    }
+
+    // Spans are sent on another task, so an unavailable collector does not delay synchronization.
+    pub fn export_finished_spans(&self) {
+        let span_records = take_finished_spans();
+        if span_records.is_empty() {
+            return;
+        }
+        let otlp_exporter = self.clone();
+        tokio::spawn(async move {
+            let request_body = get_otlp_traces_request(&span_records).to_string();
+            match otlp_exporter.send(request_body).await {
+                Ok(()) => debug!(
+                    "Exported {} spans to: '{}'.",
+                    span_records.len(),
+                    otlp_exporter.traces_url
+                ),
+                Err(err) => warn!("Failed to export spans. {:?}", err),
+            }
+        });
+    }
+
+    async fn send(&self, request_body: String) -> Result<()> {
+        self.client
+            .post(&self.traces_url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(request_body)
+            .timeout(OTLP_EXPORT_TIMEOUT)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("OTLP collector at '{}' did not accept spans.", self.traces_url))?;
+        Ok(())
+    }
+}
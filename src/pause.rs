@@ -0,0 +1,104 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeDelta, Utc};
+use configuration::{ApplicationConfigurationData, ApplicationConfigurationGetter, Pause, SyncState};
+
+use crate::cli::{PauseArgs, ResumeArgs};
+
+pub const REPLACE_MANUAL_STATUS_HINT: &str =
+    "Run `slack-gcal-sync resume --replace-status` to replace it with the calendar status on the next synchronization.";
+
+// Accepts a local time today, a date, which is the start of the day in local time, or an RFC 3339 timestamp.
+pub fn parse_until(until: &str) -> Result<DateTime<Utc>, String> {
+    parse_until_on(until, Local::now().date_naive())
+}
+
+pub fn parse_until_on(until: &str, today: NaiveDate) -> Result<DateTime<Utc>, String> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(until) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+    let local_date_time = match (
+        NaiveTime::parse_from_str(until, "%H:%M"),
+        NaiveDate::parse_from_str(until, "%Y-%m-%d"),
+    ) {
+        (Ok(time), _) => today.and_time(time),
+        (_, Ok(date)) => date.and_time(NaiveTime::MIN),
+        _ => {
+            return Err(format!(
+                "'{}' is not a time, e.g. '17:30', a date, e.g. '2025-01-20' or an RFC 3339 timestamp.",
+                until
+            ))
+        }
+    };
+    local_date_time
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|local_date_time| local_date_time.with_timezone(&Utc))
+        .ok_or(format!("'{}' does not exist in the local time zone.", until))
+}
+
+// State path only needs the merged values, the rest of the configuration does not have to be valid.
+fn get_state_path(cli_application_configuration_data: ApplicationConfigurationData) -> Result<String> {
+    let (application_configuration_data, _) = ApplicationConfigurationGetter::new(cli_application_configuration_data)?
+        .get_application_configuration_data_with_sources();
+    Ok(application_configuration_data.get_state_path())
+}
+
+// Pause without an end lasts until resumed.
+pub fn get_paused_until(
+    duration: Option<std::time::Duration>,
+    until: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>> {
+    let paused_until = match duration {
+        Some(duration) => Some(now + TimeDelta::from_std(duration).with_context(|| "Pause duration is too long.")?),
+        None => until,
+    };
+    if paused_until.is_some_and(|paused_until| paused_until <= now) {
+        bail!("Pause must end in the future.");
+    }
+    Ok(paused_until)
+}
+
+// Running daemon reads the state before every status update, so it does not have to be notified.
+pub fn run_pause(
+    pause_args: PauseArgs,
+    cli_application_configuration_data: ApplicationConfigurationData,
+) -> Result<()> {
+    let state_path = get_state_path(cli_application_configuration_data)?;
+    let now = Utc::now();
+    let paused_until = get_paused_until(pause_args.duration, pause_args.until, now)?;
+    let mut sync_state = SyncState::read(&state_path)?;
+    sync_state.pause = Some(Pause {
+        paused_at: now,
+        paused_until,
+    });
+    sync_state
+        .write(&state_path)
+        .with_context(|| "Failed to save synchronization state.")?;
+    match paused_until {
+        Some(paused_until) => println!(
+            "Slack status synchronization paused until {}.",
+            paused_until.with_timezone(&Local).to_rfc3339()
+        ),
+        None => println!("Slack status synchronization paused until resumed."),
+    }
+    Ok(())
+}
+
+// Status set manually is kept, unless replacing it is asked for, then the next status update replaces it.
+pub fn run_resume(
+    resume_args: ResumeArgs,
+    cli_application_configuration_data: ApplicationConfigurationData,
+) -> Result<()> {
+    let state_path = get_state_path(cli_application_configuration_data)?;
+    let mut sync_state = SyncState::read(&state_path)?;
+    sync_state.pause = None;
+    if resume_args.replace_status {
+        sync_state.replace_manual_status = true;
+    }
+    sync_state
+        .write(&state_path)
+        .with_context(|| "Failed to save synchronization state.")?;
+    println!("Slack status synchronization resumed.");
+    Ok(())
+}
@@ -0,0 +1,318 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveTime, Utc};
+use configuration::{ApplicationConfiguration, IdleStatus, StatusRule, WorkingHours};
+use gcal_integration::{
+    get_conference, get_date_time_in_time_zone, get_event_local_time_range, get_event_time_range, get_local_date_time,
+    get_working_location, WorkingLocation, CANCELLED_EVENT_STATUS, WORKING_LOCATION_EVENT_TYPE,
+};
+use google_calendar3::api::Event;
+use slack_integration::{ProfileData, ProfileField};
+
+pub const IN_HOURS_STATUS_RULE: &str = "working_hours.in_hours_status";
+pub const OUT_OF_HOURS_STATUS_RULE: &str = "working_hours.out_of_hours_status";
+pub const WORKING_LOCATION_STATUS_RULE_PREFIX: &str = "working_hours.working_location_statuses.";
+// Working hours are planned for the days around today, so the end of the current period and the start of the next one
+// are known even after a long weekend.
+const WORKING_HOURS_DAYS_BEFORE: i64 = 1;
+const WORKING_HOURS_DAYS_AFTER: i64 = 8;
+const JOIN_LINK_ALT: &str = "Join call";
+pub const TIME_ZONE_ENV_VAR: &str = "TZ";
+
+// Today's events, and working hours without a time zone, are in the local time zone, which follows the `TZ` environment
+// variable, or the system time zone when it is not set.
+pub fn get_local_time_zone_description(time_zone: Option<&str>, utc_offset: FixedOffset) -> String {
+    match time_zone {
+        Some(time_zone) => format!("'{}' (UTC offset {})", time_zone, utc_offset),
+        None => format!("system time zone (UTC offset {})", utc_offset),
+    }
+}
+
+// Status to set and what triggered it, recorded in the audit log.
+#[derive(Debug, PartialEq)]
+pub struct StatusChange {
+    pub profile_data: ProfileData,
+    pub event_id: Option<String>,
+    pub rule: String,
+}
+
+fn get_rule_offsets(rule: &StatusRule) -> (Duration, Duration) {
+    (
+        Duration::minutes(rule.lead_minutes.into()),
+        Duration::minutes(rule.lag_minutes.into()),
+    )
+}
+
+fn get_rule_event_time_range(rule: &StatusRule, event: &Event) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    match rule.all_day_events {
+        true => get_event_local_time_range(event),
+        false => get_event_time_range(event),
+    }
+}
+
+// Join link fields of all rules are cleared, so a link does not outlive the call once another status is set.
+fn get_join_link_fields(rules: &[StatusRule], join_link: Option<(&str, &str)>) -> BTreeMap<String, ProfileField> {
+    let mut join_link_fields: BTreeMap<String, ProfileField> = rules
+        .iter()
+        .filter_map(|rule| rule.join_link_field.clone())
+        .map(|join_link_field| (join_link_field, ProfileField::default()))
+        .collect();
+    if let Some((join_link_field, join_url)) = join_link {
+        join_link_fields.insert(
+            join_link_field.to_string(),
+            ProfileField {
+                value: join_url.to_string(),
+                alt: JOIN_LINK_ALT.to_string(),
+            },
+        );
+    }
+    join_link_fields
+}
+
+// Working hours are in their time zone if it is set, otherwise in the local time zone.
+fn get_working_hours_date(working_hours: Option<&WorkingHours>, instant: DateTime<Utc>) -> NaiveDate {
+    match working_hours.and_then(WorkingHours::get_time_zone) {
+        Some(time_zone) => instant.with_timezone(&time_zone).date_naive(),
+        None => instant.with_timezone(&Local).date_naive(),
+    }
+}
+
+fn get_working_hours_date_time(working_hours: &WorkingHours, date: NaiveDate, time: NaiveTime) -> DateTime<Utc> {
+    match working_hours.get_time_zone() {
+        Some(time_zone) => get_date_time_in_time_zone(date, time, &time_zone),
+        None => get_local_date_time(date, time),
+    }
+}
+
+// User is back on the day the event ends during working hours, otherwise when the next working hours start. Without
+// working hours the user is back on the day the event ends.
+pub fn get_return_date(return_at: DateTime<Utc>, working_hours: Option<&WorkingHours>) -> NaiveDate {
+    let back_at = working_hours
+        .and_then(|working_hours| {
+            get_working_periods(working_hours, return_at)
+                .into_iter()
+                .find(|(_, end)| *end > return_at)
+                .map(|(start, _)| start.max(return_at))
+        })
+        .unwrap_or(return_at);
+    get_working_hours_date(working_hours, back_at)
+}
+
+// Rules are checked in the configured order, so the first rule matching any event ongoing within its lead and lag offsets
+// wins. Status expires when the event ends plus the lag of the rule, so it is cleared by Slack even if the next
+// synchronization does not run, e.g. when a week long vacation ends. Working location events are not meetings and never
+// match rules. Video call of the event sets the emoji of its conference provider and the join link field of the rule.
+fn get_rule_status_change(
+    events: &[Event],
+    application_configuration: &ApplicationConfiguration,
+    now: DateTime<Utc>,
+) -> Option<StatusChange> {
+    application_configuration.rules.iter().find_map(|rule| {
+        let (lead, lag) = get_rule_offsets(rule);
+        events
+            .iter()
+            .filter(|event| event.event_type.as_deref() != Some(WORKING_LOCATION_EVENT_TYPE))
+            .filter(|event| event.status.as_deref() != Some(CANCELLED_EVENT_STATUS))
+            .filter(|event| rule.matches_event_type(event.event_type.as_deref()))
+            .filter(|event| rule.matches(event.summary.as_deref().unwrap_or_default()))
+            .find_map(|event| {
+                let (start, end) = get_rule_event_time_range(rule, event)?;
+                (start - lead <= now && now < end + lag).then_some((event, end + lag))
+            })
+            .map(|(event, return_at)| {
+                let conference = get_conference(event);
+                let join_link = rule
+                    .join_link_field
+                    .as_deref()
+                    .zip(conference.as_ref().map(|conference| conference.join_url.as_str()));
+                StatusChange {
+                    profile_data: ProfileData {
+                        status_text: rule.get_status_text(get_return_date(
+                            return_at,
+                            application_configuration.working_hours.as_ref(),
+                        )),
+                        status_emoji: rule
+                            .get_status_emoji(conference.as_ref().map(|conference| conference.provider.get_name())),
+                        status_expiration: return_at.timestamp(),
+                        fields: get_join_link_fields(&application_configuration.rules, join_link),
+                    },
+                    event_id: event.id.clone(),
+                    rule: rule.summary_pattern.to_string(),
+                }
+            })
+    })
+}
+
+fn get_working_periods(working_hours: &WorkingHours, now: DateTime<Utc>) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let today = get_working_hours_date(Some(working_hours), now);
+    (-WORKING_HOURS_DAYS_BEFORE..=WORKING_HOURS_DAYS_AFTER)
+        .map(|days| today + Duration::days(days))
+        .filter_map(|date: NaiveDate| {
+            working_hours.get_hours(date.weekday()).map(|(start, end)| {
+                (
+                    get_working_hours_date_time(working_hours, date, start),
+                    get_working_hours_date_time(working_hours, date, end),
+                )
+            })
+        })
+        .collect()
+}
+
+fn get_working_location_status(
+    working_hours: &WorkingHours,
+    working_location: WorkingLocation,
+) -> Option<(&IdleStatus, &'static str)> {
+    let working_location_statuses = &working_hours.working_location_statuses;
+    match working_location {
+        WorkingLocation::Home => working_location_statuses.home.as_ref().map(|status| (status, "home")),
+        WorkingLocation::Office => working_location_statuses
+            .office
+            .as_ref()
+            .map(|status| (status, "office")),
+        WorkingLocation::Custom => working_location_statuses
+            .custom
+            .as_ref()
+            .map(|status| (status, "custom")),
+    }
+}
+
+// Empty idle status clears the Slack status, which has no expiration.
+fn get_idle_status_change(
+    idle_status: &IdleStatus,
+    expires_at: Option<DateTime<Utc>>,
+    event_id: Option<String>,
+    rule: String,
+) -> StatusChange {
+    let is_empty = idle_status.status_text.is_empty() && idle_status.status_emoji.is_empty();
+    StatusChange {
+        profile_data: ProfileData {
+            status_text: idle_status.status_text.clone(),
+            status_emoji: idle_status.status_emoji.clone(),
+            status_expiration: match is_empty {
+                true => 0,
+                false => expires_at.map(|expires_at| expires_at.timestamp()).unwrap_or_default(),
+            },
+            fields: BTreeMap::new(),
+        },
+        event_id,
+        rule,
+    }
+}
+
+// During working hours the status of today's working location is used if configured, otherwise the in-hours status. Idle
+// statuses expire when the working hours, or the working location event, end.
+fn get_working_hours_status_change(events: &[Event], working_hours: &WorkingHours, now: DateTime<Utc>) -> StatusChange {
+    let working_periods = get_working_periods(working_hours, now);
+    let Some((_, end_of_working_hours)) = working_periods.iter().find(|(start, end)| *start <= now && now < *end)
+    else {
+        let start_of_working_hours = working_periods
+            .iter()
+            .map(|(start, _)| *start)
+            .find(|start| *start > now);
+        return get_idle_status_change(
+            &working_hours.out_of_hours_status,
+            start_of_working_hours,
+            None,
+            OUT_OF_HOURS_STATUS_RULE.to_string(),
+        );
+    };
+    let working_location_status = events
+        .iter()
+        .filter(|event| event.status.as_deref() != Some(CANCELLED_EVENT_STATUS))
+        .filter_map(|event| {
+            let (start, end) = get_event_local_time_range(event)?;
+            let (idle_status, working_location) =
+                get_working_location_status(working_hours, get_working_location(event)?)?;
+            (start <= now && now < end).then_some((event, end, idle_status, working_location))
+        })
+        .next();
+    match working_location_status {
+        Some((event, end_of_working_location, idle_status, working_location)) => get_idle_status_change(
+            idle_status,
+            Some(end_of_working_location.min(*end_of_working_hours)),
+            event.id.clone(),
+            format!("{}{}", WORKING_LOCATION_STATUS_RULE_PREFIX, working_location),
+        ),
+        None => get_idle_status_change(
+            &working_hours.in_hours_status,
+            Some(*end_of_working_hours),
+            None,
+            IN_HOURS_STATUS_RULE.to_string(),
+        ),
+    }
+}
+
+// Status rules win over working hours. Without working hours no status is set when no rule matches.
+pub fn get_status_change(
+    events: &[Event],
+    application_configuration: &ApplicationConfiguration,
+    now: DateTime<Utc>,
+) -> Option<StatusChange> {
+    get_rule_status_change(events, application_configuration, now).or_else(|| {
+        application_configuration.working_hours.as_ref().map(|working_hours| {
+            let mut status_change = get_working_hours_status_change(events, working_hours, now);
+            status_change.profile_data.fields = get_join_link_fields(&application_configuration.rules, None);
+            status_change
+        })
+    })
+}
+
+// Status set from `at` on. No status change means no rule matches and the status is kept.
+#[derive(Debug, PartialEq)]
+pub struct StatusTransition {
+    pub at: DateTime<Utc>,
+    pub status_change: Option<StatusChange>,
+}
+
+// Status can only change when an event starts or ends, shifted by lead and lag offsets of any rule, or when working hours
+// start or end, so it is resolved at these instants, including boundaries of overlapping events. Instants which do not
+// change the resolved status are left out.
+pub fn get_status_timeline(
+    events: &[Event],
+    application_configuration: &ApplicationConfiguration,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Vec<StatusTransition> {
+    let mut instants: Vec<DateTime<Utc>> = application_configuration
+        .rules
+        .iter()
+        .flat_map(|rule| {
+            let (lead, lag) = get_rule_offsets(rule);
+            events
+                .iter()
+                .filter_map(|event| get_rule_event_time_range(rule, event))
+                .flat_map(move |(start, end)| [start - lead, end + lag])
+        })
+        .collect();
+    if let Some(working_hours) = application_configuration.working_hours.as_ref() {
+        instants.extend(
+            get_working_periods(working_hours, from)
+                .into_iter()
+                .flat_map(|(start, end)| [start, end]),
+        );
+        instants.extend(
+            events
+                .iter()
+                .filter(|event| get_working_location(event).is_some())
+                .filter_map(get_event_local_time_range)
+                .flat_map(|(start, end)| [start, end]),
+        );
+    }
+    instants.retain(|instant| from < *instant && *instant < to);
+    instants.push(from);
+    instants.sort();
+    instants.dedup();
+
+    let mut status_timeline: Vec<StatusTransition> = vec![];
+    for at in instants {
+        let status_change = get_status_change(events, application_configuration, at);
+        if status_timeline
+            .last()
+            .is_some_and(|status_transition| status_transition.status_change == status_change)
+        {
+            continue;
+        }
+        status_timeline.push(StatusTransition { at, status_change });
+    }
+    status_timeline
+}
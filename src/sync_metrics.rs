@@ -0,0 +1,180 @@
+use std::{future::Future, time::Duration};
+
+use anyhow::{Context, Result};
+use gcal_integration::GoogleCalendarIntegrationError;
+use google_calendar3::api::Event;
+use logging::Span;
+use metrics::{counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram, Unit};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use slack_integration::{AuthTestResponse, ProfileResponse, SlackApiError, RATE_LIMIT_WAITS_METRIC};
+use tokio::time::{interval, Instant};
+
+pub const METRICS_UPKEEP_INTERVAL: Duration = Duration::from_secs(5);
+pub const CALENDAR_EVENTS_FETCHED_METRIC: &str = "slack_gcal_sync_calendar_events_fetched_total";
+pub const API_CALL_DURATION_METRIC: &str = "slack_gcal_sync_api_call_duration_seconds";
+pub const API_CALL_ERRORS_METRIC: &str = "slack_gcal_sync_api_call_errors_total";
+pub const STATUS_UPDATES_METRIC: &str = "slack_gcal_sync_status_updates_total";
+pub const LAST_SUCCESSFUL_SYNC_METRIC: &str = "slack_gcal_sync_last_successful_sync_timestamp_seconds";
+pub const GOOGLE_API: &str = "google";
+pub const SLACK_API: &str = "slack";
+pub const GOOGLE_EVENTS_LIST_METHOD: &str = "events.list";
+pub const GOOGLE_ACCESS_TOKEN_METHOD: &str = "token";
+pub const STATUS_UPDATE_APPLIED: &str = "applied";
+pub const STATUS_UPDATE_SKIPPED: &str = "skipped";
+pub const STATUS_UPDATE_PAUSED: &str = "paused";
+pub const STATUS_UPDATE_MANUAL_STATUS: &str = "manual_status";
+// Slack and Google API calls take from tens of milliseconds to several seconds when rate limited.
+const API_CALL_DURATION_BUCKETS: [f64; 10] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+
+// Errors are counted by their variant, messages would create a new time series for every calendar or url.
+pub trait ApiError {
+    fn get_error_variant(&self) -> &'static str;
+}
+
+impl ApiError for GoogleCalendarIntegrationError {
+    fn get_error_variant(&self) -> &'static str {
+        match self {
+            GoogleCalendarIntegrationError::GoogleCalendarCommunicationError(_) => "GoogleCalendarCommunicationError",
+            GoogleCalendarIntegrationError::AccessTokenError(_) => "AccessTokenError",
+            GoogleCalendarIntegrationError::IOError(_) => "IOError",
+            GoogleCalendarIntegrationError::CalendarNotFoundError(_) => "CalendarNotFoundError",
+            GoogleCalendarIntegrationError::CalendarAccessForbiddenError(..) => "CalendarAccessForbiddenError",
+        }
+    }
+}
+
+impl ApiError for SlackApiError {
+    fn get_error_variant(&self) -> &'static str {
+        match self {
+            SlackApiError::InvalidAuthError => "InvalidAuthError",
+            SlackApiError::UnknownSlackApiMethodError(_) => "UnknownSlackApiMethodError",
+            SlackApiError::MissingScopesError(_) => "MissingScopesError",
+            SlackApiError::SlackAPIError(_) => "SlackAPIError",
+            SlackApiError::RateLimitedError(_) => "RateLimitedError",
+            SlackApiError::ResponseError(_) => "ResponseError",
+        }
+    }
+}
+
+// Metrics are recorded only when the recorder is installed, otherwise recording them does nothing. Must be called
+// within the tokio runtime.
+pub fn install_metrics_recorder() -> Result<PrometheusHandle> {
+    let prometheus_handle = PrometheusBuilder::new()
+        .set_buckets_for_metric(
+            Matcher::Full(API_CALL_DURATION_METRIC.to_string()),
+            &API_CALL_DURATION_BUCKETS,
+        )?
+        .install_recorder()
+        .with_context(|| "Failed to install metrics recorder.")?;
+    describe_counter!(
+        CALENDAR_EVENTS_FETCHED_METRIC,
+        "Number of google calendar events fetched per calendar."
+    );
+    describe_histogram!(
+        API_CALL_DURATION_METRIC,
+        Unit::Seconds,
+        "Duration of Google and Slack API calls."
+    );
+    describe_counter!(
+        API_CALL_ERRORS_METRIC,
+        "Number of failed Google and Slack API calls by error variant."
+    );
+    describe_counter!(
+        STATUS_UPDATES_METRIC,
+        "Number of Slack status updates applied or skipped because the status was already set."
+    );
+    describe_counter!(
+        RATE_LIMIT_WAITS_METRIC,
+        "Number of waits for rate limited API calls before sending them again."
+    );
+    describe_gauge!(
+        LAST_SUCCESSFUL_SYNC_METRIC,
+        Unit::Seconds,
+        "Unix timestamp of the last successful synchronization per Slack user."
+    );
+    // Histograms are drained periodically to keep their memory bounded between scrapes.
+    let upkeep_prometheus_handle = prometheus_handle.clone();
+    tokio::spawn(async move {
+        let mut upkeep_interval = interval(METRICS_UPKEEP_INTERVAL);
+        loop {
+            upkeep_interval.tick().await;
+            upkeep_prometheus_handle.run_upkeep();
+        }
+    });
+    Ok(prometheus_handle)
+}
+
+// Attributes of successful API calls added to their spans.
+pub trait ApiResponse {
+    fn get_span_attributes(&self) -> Vec<(&'static str, String)>;
+}
+
+impl ApiResponse for () {
+    fn get_span_attributes(&self) -> Vec<(&'static str, String)> {
+        vec![]
+    }
+}
+
+impl ApiResponse for Vec<Event> {
+    fn get_span_attributes(&self) -> Vec<(&'static str, String)> {
+        vec![("event_count", self.len().to_string())]
+    }
+}
+
+impl ApiResponse for ProfileResponse {
+    fn get_span_attributes(&self) -> Vec<(&'static str, String)> {
+        vec![("http_status", self.status.as_u16().to_string())]
+    }
+}
+
+impl ApiResponse for AuthTestResponse {
+    fn get_span_attributes(&self) -> Vec<(&'static str, String)> {
+        vec![("http_status", self.status.as_u16().to_string())]
+    }
+}
+
+// Each call gets a span named after the API method, child of the span open when it is called.
+pub async fn observe_api_call<T: ApiResponse, E: ApiError>(
+    api: &'static str,
+    method: &'static str,
+    attributes: &[(&'static str, &str)],
+    api_call: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    let mut span = Span::start(&format!("{}.{}", api, method));
+    for (key, value) in attributes {
+        span.set_attribute(key, value);
+    }
+    let started_at = Instant::now();
+    let result = api_call.await;
+    histogram!(API_CALL_DURATION_METRIC, "api" => api, "method" => method).record(started_at.elapsed());
+    match &result {
+        Ok(response) => {
+            for (key, value) in response.get_span_attributes() {
+                span.set_attribute(key, value);
+            }
+        }
+        Err(err) => {
+            span.set_error(err.get_error_variant());
+            counter!(
+                API_CALL_ERRORS_METRIC,
+                "api" => api,
+                "method" => method,
+                "error" => err.get_error_variant()
+            )
+            .increment(1);
+        }
+    }
+    result
+}
+
+pub fn record_calendar_events_fetched(calendar_id: &str, count: usize) {
+    counter!(CALENDAR_EVENTS_FETCHED_METRIC, "calendar_id" => calendar_id.to_string()).increment(count as u64);
+}
+
+pub fn record_status_update(result: &'static str) {
+    counter!(STATUS_UPDATES_METRIC, "result" => result).increment(1);
+}
+
+pub fn record_successful_sync(user: &str, timestamp: i64) {
+    gauge!(LAST_SUCCESSFUL_SYNC_METRIC, "user" => user.to_string()).set(timestamp as f64);
+}
@@ -0,0 +1,72 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Local, Utc};
+use configuration::{ApplicationConfigurationData, ApplicationConfigurationGetter};
+use gcal_integration::{
+    get_calendar_events_for_today, get_calendar_hub, get_service_account_authenticator, get_start_of_local_day,
+};
+
+use crate::status::{get_status_timeline, StatusTransition};
+
+fn format_local_time(instant: DateTime<Utc>) -> String {
+    instant.with_timezone(&Local).format("%H:%M:%S").to_string()
+}
+
+fn get_status_transition_description(status_transition: &StatusTransition) -> String {
+    match &status_transition.status_change {
+        Some(status_change) => format!(
+            "'{} {}' {} (event '{}', rule '{}')",
+            status_change.profile_data.status_emoji,
+            status_change.profile_data.status_text,
+            match status_change.profile_data.status_expiration {
+                0 => "without expiration".to_string(),
+                status_expiration => format!(
+                    "until {}",
+                    format_local_time(DateTime::from_timestamp(status_expiration, 0).unwrap_or_default())
+                ),
+            },
+            status_change.event_id.as_deref().unwrap_or_default(),
+            status_change.rule
+        ),
+        None => "no rule matches and no working hours are set, status is kept".to_string(),
+    }
+}
+
+// Same events and rules as used by synchronization, so the printed transitions are the ones the daemon fires.
+pub async fn run_timeline(cli_application_configuration_data: ApplicationConfigurationData) -> Result<()> {
+    let google_api_error_context = || "Failed to integrate with Google API using supplied service account key.";
+    let application_configuration = ApplicationConfigurationGetter::new(cli_application_configuration_data)?
+        .get_application_configuration()
+        .with_context(|| "Failed to configure application.")?;
+    let service_account_authenticator =
+        get_service_account_authenticator(application_configuration.service_account_key.expose_secret().clone())
+            .await
+            .with_context(google_api_error_context)?;
+    let hub = get_calendar_hub(service_account_authenticator).with_context(google_api_error_context)?;
+    let events = get_calendar_events_for_today(hub, &application_configuration.calendar_id)
+        .await
+        .with_context(google_api_error_context)?;
+
+    let now = Utc::now();
+    let today = Local::now().date_naive();
+    let start_of_day = get_start_of_local_day(today);
+    let end_of_day = get_start_of_local_day(today + Duration::days(1));
+    let status_timeline = get_status_timeline(&events, &application_configuration, start_of_day, end_of_day);
+    println!(
+        "Planned Slack status transitions for {} (times in local time zone):",
+        today.format("%Y-%m-%d")
+    );
+    for status_transition in &status_timeline {
+        let marker = match status_transition.at <= now {
+            true => " ",
+            false => "*",
+        };
+        println!(
+            "{} {}  {}",
+            marker,
+            format_local_time(status_transition.at),
+            get_status_transition_description(status_transition)
+        );
+    }
+    println!("Transitions marked with * are still to come.");
+    Ok(())
+}